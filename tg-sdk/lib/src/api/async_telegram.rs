@@ -0,0 +1,310 @@
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use super::{
+    attach_url, check_download, decode_result, file_url, multipart, reply_to, AsyncTransport,
+    HttpRequest, Method, BASE_URL,
+};
+use crate::{File, InputFile, Message};
+
+/// An async client for the Telegram Bot API.
+///
+/// It has the same methods as [`Telegram`](crate::Telegram), but each of them
+/// returns a future instead of blocking, so concurrent calls don't wait for
+/// each other. By default requests are sent with [`reqwest`], which makes it
+/// usable on ordinary native tokio targets.
+///
+/// Only available with the `async` feature.
+#[derive(Clone)]
+pub struct AsyncTelegram {
+    base_url: &'static str,
+    token: String,
+    transport: Arc<dyn AsyncTransport>,
+}
+
+impl AsyncTelegram {
+    pub fn new(token: String) -> Self {
+        Self::new_with_base_url(token, BASE_URL)
+    }
+
+    pub fn new_with_base_url(token: String, base_url: &'static str) -> Self {
+        Self {
+            base_url,
+            token,
+            transport: Arc::new(reqwest::Client::new()),
+        }
+    }
+
+    /// Sends requests through `transport` instead of the default one.
+    pub fn with_transport<T>(mut self, transport: T) -> Self
+    where
+        T: AsyncTransport + 'static,
+    {
+        self.transport = Arc::new(transport);
+        self
+    }
+}
+
+impl AsyncTelegram {
+    pub fn request<'a, T>(
+        &'a self,
+        method: Method,
+        body: &[u8],
+    ) -> impl Future<Output = Result<T>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+    {
+        let url = format!("{}{}/{}", self.base_url, self.token, method);
+        self.send(Ok(url), "application/json", body)
+    }
+
+    pub fn request_with_attach<'a, T, S>(
+        &'a self,
+        method: Method,
+        query: HashMap<S, S>,
+        body: &[u8],
+    ) -> impl Future<Output = Result<T>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+        S: Serialize,
+    {
+        let url = attach_url(self.base_url, &self.token, method, query);
+        self.send(url, "multipart/form-data", body)
+    }
+
+    /// Sends `fields` along with the `files` they refer to, as
+    /// `multipart/form-data` if any of them has to be uploaded.
+    pub fn request_multipart<'a, T>(
+        &'a self,
+        method: Method,
+        fields: Value,
+        files: Vec<InputFile>,
+    ) -> impl Future<Output = Result<T>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+    {
+        let url = format!("{}{}/{}", self.base_url, self.token, method);
+        let response = multipart::encode(&fields, files).map(|(content_type, body)| {
+            let request = HttpRequest::post(url, &content_type, body);
+            self.transport.send(request)
+        });
+
+        async move { decode_result(&response?.await?.body) }
+    }
+
+    /// Sends `bodies` one after the other, each replying to the message sent
+    /// by the one before.
+    pub async fn request_replies(
+        &self,
+        method: Method,
+        bodies: Vec<Value>,
+    ) -> Result<Vec<Message>> {
+        let mut sent: Vec<Message> = Vec::new();
+        for body in bodies {
+            let body = reply_to(body, sent.last());
+            sent.push(self.request(method, body.to_string().as_bytes()).await?);
+        }
+        Ok(sent)
+    }
+
+    /// Downloads the contents of `file`, from [`get_file`], see
+    /// [`Telegram::download_file`](crate::Telegram::download_file).
+    ///
+    /// [`get_file`]: AsyncTelegram::get_file
+    pub async fn download_file(&self, file: &File) -> Result<Bytes> {
+        let url = file_url(self.base_url, &self.token, file);
+        let response = self
+            .transport
+            .send(HttpRequest::get(url))
+            .await
+            .with_context(|| format!("downloading {} failed", file.path))?;
+
+        check_download(file, &response, response.body.len() as u64)?;
+        Ok(response.body.into())
+    }
+
+    /// Downloads the file with `file_id` with [`get_file`] and
+    /// [`download_file`].
+    ///
+    /// [`get_file`]: AsyncTelegram::get_file
+    /// [`download_file`]: AsyncTelegram::download_file
+    pub async fn download_by_id<S>(&self, file_id: S) -> Result<Bytes>
+    where
+        S: Into<String>,
+    {
+        self.download_file(&self.get_file(file_id.into()).await?)
+            .await
+    }
+
+    fn send<'a, T>(
+        &'a self,
+        url: Result<String>,
+        content_type: &str,
+        body: &[u8],
+    ) -> impl Future<Output = Result<T>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+    {
+        let response = url.map(|url| {
+            let request = HttpRequest::post(url, content_type, body.to_vec());
+            self.transport.send(request)
+        });
+
+        async move { decode_result(&response?.await?.body) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use serde_json::json;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use crate::{ApiError, AsyncTelegram, ChatId, HttpResponse, ReplayTransport};
+
+    /// Serves a single canned Bot API response and returns the raw request.
+    async fn serve_once(listener: TcpListener, result: &str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let request = read_request(&mut stream).await;
+        respond(&mut stream, result).await;
+
+        request
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+
+            let text = String::from_utf8_lossy(&request);
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let len = head
+                    .lines()
+                    .find_map(|l| l.strip_prefix("content-length: "))
+                    .map_or(0, |l| l.parse().unwrap());
+                if body.len() >= len {
+                    break;
+                }
+            }
+        }
+
+        String::from_utf8(request).unwrap()
+    }
+
+    async fn respond(stream: &mut TcpStream, result: &str) {
+        let body = format!(r#"{{"ok":true,"result":{result}}}"#);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+    }
+
+    async fn client() -> (AsyncTelegram, TcpListener) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/bot", listener.local_addr().unwrap());
+        let tele = AsyncTelegram::new_with_base_url("TOKEN".to_owned(), base_url.leak());
+
+        (tele, listener)
+    }
+
+    #[tokio::test]
+    async fn get_me() {
+        let (tele, listener) = client().await;
+        let server = tokio::spawn(serve_once(
+            listener,
+            r#"{"id":1,"is_bot":true,"first_name":"bot","username":"a_bot","can_join_groups":true,"can_read_all_group_messages":false,"supports_inline_queries":false}"#,
+        ));
+
+        let me = tele.get_me().await.unwrap();
+        let request = server.await.unwrap();
+
+        assert_eq!(me.username(), "a_bot");
+        assert!(request.starts_with("POST /botTOKEN/getMe "));
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests() {
+        let (tele, listener) = client().await;
+
+        // Only answers once both requests are in, so a client sending them one
+        // after the other times out.
+        let server = async {
+            let mut streams = Vec::new();
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let request = read_request(&mut stream).await;
+                streams.push((stream, request));
+            }
+            for (stream, request) in &mut streams {
+                let text = if request.ends_with(r#""text":"a"}"#) {
+                    "a"
+                } else {
+                    "b"
+                };
+                let result = format!(
+                    r#"{{"message_id":1,"date":0,"chat":{{"id":1,"type":"private"}},"text":"{text}"}}"#
+                );
+                respond(stream, &result).await;
+            }
+        };
+
+        let (a, b, ()) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(
+                tele.send_message(ChatId(1), "a"),
+                tele.send_message(ChatId(1), "b"),
+                server
+            )
+        })
+        .await
+        .expect("requests were sent one after the other");
+
+        assert_eq!(a.unwrap().text(), Some("a"));
+        assert_eq!(b.unwrap().text(), Some("b"));
+    }
+
+    #[tokio::test]
+    async fn download_file() {
+        let replay = Arc::new(ReplayTransport::default());
+        let file = json!({
+            "file_id": "f1",
+            "file_unique_id": "u1",
+            "file_size": 5,
+            "file_path": "photos/file_1.jpg",
+        });
+        replay.push_result(file.clone());
+        replay.push(HttpResponse::new(200, "hello"));
+        replay.push(HttpResponse::new(200, "hell"));
+        replay.push_error(404, "Not Found");
+        let tele = AsyncTelegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        assert_eq!(tele.download_by_id("f1").await.unwrap(), "hello");
+
+        let file = serde_json::from_value(file).unwrap();
+        let err = tele.download_file(&file).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "downloaded 4 bytes of photos/file_1.jpg, expected 5"
+        );
+        let err = tele.download_file(&file).await.unwrap_err();
+        assert_eq!(err.downcast::<ApiError>().unwrap().error_code, 404);
+
+        let requests = replay.requests();
+        assert_eq!(requests[1].method, "GET");
+        assert_eq!(
+            requests[1].url,
+            "https://api.telegram.org/file/botTOKEN/photos/file_1.jpg"
+        );
+    }
+}
@@ -0,0 +1,537 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::Duration,
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{ApiError, ChatId, MessageId, RateLimiter, ResponseParameters, Telegram};
+
+/// A message sent to every chat of a [`Broadcast`].
+pub trait MessageTemplate {
+    /// Sends the message to `chat_id`, returning the id of the sent message.
+    fn send(&self, tele: &Telegram, chat_id: ChatId) -> Result<MessageId>;
+}
+
+impl MessageTemplate for str {
+    fn send(&self, tele: &Telegram, chat_id: ChatId) -> Result<MessageId> {
+        tele.send_message(chat_id, self).map(|message| message.id)
+    }
+}
+
+impl MessageTemplate for String {
+    fn send(&self, tele: &Telegram, chat_id: ChatId) -> Result<MessageId> {
+        tele.send_message(chat_id, self.as_str())
+            .map(|message| message.id)
+    }
+}
+
+/// Sends whatever the closure sends, e.g. a message personalized per chat.
+impl<F> MessageTemplate for F
+where
+    F: Fn(&Telegram, ChatId) -> Result<MessageId>,
+{
+    fn send(&self, tele: &Telegram, chat_id: ChatId) -> Result<MessageId> {
+        self(tele, chat_id)
+    }
+}
+
+/// Copies an existing message to every chat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CopyOf {
+    pub from_chat_id: ChatId,
+    pub message_id: MessageId,
+}
+
+impl MessageTemplate for CopyOf {
+    fn send(&self, tele: &Telegram, chat_id: ChatId) -> Result<MessageId> {
+        tele.copy_message(chat_id, self.from_chat_id, self.message_id)
+    }
+}
+
+/// Why a message couldn't be delivered to a chat.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryFailure {
+    /// The user blocked the bot.
+    BlockedByUser,
+
+    /// The chat doesn't exist or the bot never talked to the user.
+    ChatNotFound,
+
+    /// The user's account was deleted.
+    UserDeactivated,
+
+    /// The bot was removed from the group or channel.
+    KickedFromChat,
+
+    /// Any other Bot API error, with its description.
+    Other(String),
+}
+
+impl DeliveryFailure {
+    /// Classifies a failed Bot API response.
+    #[must_use]
+    pub fn from_api_error(error: &ApiError) -> Self {
+        let description = error.description.to_lowercase();
+
+        if description.contains("bot was blocked by the user") {
+            Self::BlockedByUser
+        } else if description.contains("chat not found") {
+            Self::ChatNotFound
+        } else if description.contains("user is deactivated") {
+            Self::UserDeactivated
+        } else if description.contains("bot was kicked") {
+            Self::KickedFromChat
+        } else {
+            Self::Other(error.description.clone())
+        }
+    }
+
+    /// Returns `true` if sending to the chat again will fail the same way.
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        !matches!(self, Self::Other(_))
+    }
+}
+
+/// Outcome of a [`Broadcast`], accumulated over all of its runs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BroadcastReport {
+    pub delivered: Vec<ChatId>,
+
+    pub failed: Vec<(ChatId, DeliveryFailure)>,
+
+    /// Chats passed to [`Broadcast::skip`] and repeated chats.
+    pub skipped: Vec<ChatId>,
+
+    /// Number of chats left for a later run.
+    pub remaining: usize,
+}
+
+impl BroadcastReport {
+    /// Returns `true` if every chat has been handled.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Returns the chats that failed permanently, e.g. to
+    /// [`skip`](Broadcast::skip) them next time.
+    pub fn unreachable(&self) -> impl Iterator<Item = ChatId> + '_ {
+        self.failed
+            .iter()
+            .filter(|(_, failure)| failure.is_permanent())
+            .map(|(chat_id, _)| *chat_id)
+    }
+}
+
+/// Progress of a [`Broadcast`], saved after every chat.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BroadcastState {
+    /// Index of the next chat to send to.
+    pub cursor: usize,
+
+    pub report: BroadcastReport,
+}
+
+/// Where [`Broadcast`]s keep their progress between runs.
+pub trait BroadcastStore {
+    fn load(&self, id: &str) -> Result<Option<BroadcastState>>;
+
+    fn save(&self, id: &str, state: &BroadcastState) -> Result<()>;
+}
+
+impl<S> BroadcastStore for &S
+where
+    S: BroadcastStore + ?Sized,
+{
+    fn load(&self, id: &str) -> Result<Option<BroadcastState>> {
+        (**self).load(id)
+    }
+
+    fn save(&self, id: &str, state: &BroadcastState) -> Result<()> {
+        (**self).save(id, state)
+    }
+}
+
+/// A [`BroadcastStore`] that lives as long as the process does.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    states: Mutex<HashMap<String, BroadcastState>>,
+}
+
+impl MemoryStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl BroadcastStore for MemoryStore {
+    fn load(&self, id: &str) -> Result<Option<BroadcastState>> {
+        Ok(self.states.lock().unwrap().get(id).cloned())
+    }
+
+    fn save(&self, id: &str, state: &BroadcastState) -> Result<()> {
+        self.states
+            .lock()
+            .unwrap()
+            .insert(id.to_owned(), state.clone());
+        Ok(())
+    }
+}
+
+/// Sends a message to many chats, within Telegram's rate limits.
+///
+/// Progress is saved to a [`BroadcastStore`] under the broadcast id after
+/// every chat, so a broadcast cut short, by [`Broadcast::time_budget`] or by
+/// the flow timing out, picks up where it stopped when it's run again with
+/// the same id and chats. Flows don't outlive a single run, so this takes a
+/// store that persists elsewhere, a [`MemoryStore`] only resumes within the
+/// process.
+///
+/// Failed deliveries are recorded in the [`BroadcastReport`], while anything
+/// that isn't a Bot API error (e.g. the network being down) stops the run and
+/// is returned, the chat is retried by the next run. A chat still hitting
+/// flood control after [`Broadcast::max_retries`] retries is failed.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use tg_flows::{BroadcastStore, ChatId, Telegram};
+///
+/// # fn run(tele: Telegram, subscribers: Vec<ChatId>, store: impl BroadcastStore) -> anyhow::Result<()> {
+/// let report = tele
+///     .broadcast("release-0.5", subscribers, store)
+///     .time_budget(Duration::from_secs(50))
+///     .send("tg-flows 0.5 is out!")?;
+///
+/// if !report.is_finished() {
+///     // run again later
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Broadcast<'a> {
+    tele: &'a Telegram,
+    id: String,
+    chat_ids: Vec<ChatId>,
+    skip: HashSet<ChatId>,
+    store: Box<dyn BroadcastStore + 'a>,
+    limiter: RateLimiter,
+    max_retries: usize,
+    time_budget: Option<Duration>,
+}
+
+impl<'a> Broadcast<'a> {
+    /// Paces messages with `limiter` instead of a default [`RateLimiter`].
+    #[must_use]
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Sets how many times a chat rejected with HTTP 429 is retried before
+    /// it's failed, 3 by default.
+    #[must_use]
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Doesn't send to `chat_ids`, e.g. users known to have blocked the bot.
+    #[must_use]
+    pub fn skip<I>(mut self, chat_ids: I) -> Self
+    where
+        I: IntoIterator<Item = ChatId>,
+    {
+        self.skip.extend(chat_ids);
+        self
+    }
+
+    /// Stops sending once `budget` has passed, leaving the rest for a later
+    /// run.
+    #[must_use]
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Sends `template` to the chats not handled by previous runs.
+    pub fn send<T>(&self, template: &T) -> Result<BroadcastReport>
+    where
+        T: MessageTemplate + ?Sized,
+    {
+        let mut state = self.store.load(&self.id)?.unwrap_or_default();
+        let mut seen: HashSet<_> = self.chat_ids[..state.cursor.min(self.chat_ids.len())]
+            .iter()
+            .copied()
+            .collect();
+
+        let deadline = self.time_budget.map(|budget| self.limiter.now() + budget);
+
+        while let Some(&chat_id) = self.chat_ids.get(state.cursor) {
+            if deadline.is_some_and(|deadline| self.limiter.now() >= deadline) {
+                break;
+            }
+
+            if self.skip.contains(&chat_id) || !seen.insert(chat_id) {
+                state.report.skipped.push(chat_id);
+            } else {
+                match self.deliver(template, chat_id)? {
+                    None => state.report.delivered.push(chat_id),
+                    Some(failure) => state.report.failed.push((chat_id, failure)),
+                }
+            }
+
+            state.cursor += 1;
+            state.report.remaining = self.chat_ids.len() - state.cursor;
+            self.store.save(&self.id, &state)?;
+        }
+
+        state.report.remaining = self.chat_ids.len().saturating_sub(state.cursor);
+        Ok(state.report)
+    }
+
+    /// Sends to a single chat, waiting out flood control.
+    fn deliver<T>(&self, template: &T, chat_id: ChatId) -> Result<Option<DeliveryFailure>>
+    where
+        T: MessageTemplate + ?Sized,
+    {
+        let mut retries = 0;
+        loop {
+            self.limiter.wait(Some(chat_id));
+
+            let error = match template.send(self.tele, chat_id) {
+                Ok(_) => return Ok(None),
+                Err(e) => e,
+            };
+
+            match error.downcast_ref::<ApiError>() {
+                Some(ApiError {
+                    parameters: Some(ResponseParameters::RetryAfter(retry_after)),
+                    ..
+                }) if retries < self.max_retries => {
+                    retries += 1;
+                    self.limiter.sleep(*retry_after);
+                }
+                Some(error) => return Ok(Some(DeliveryFailure::from_api_error(error))),
+                None => return Err(error),
+            }
+        }
+    }
+}
+
+impl Telegram {
+    /// Prepares sending a message to each of `chat_ids`, see [`Broadcast`].
+    ///
+    /// `id` names the broadcast in `store`, which keeps its progress.
+    pub fn broadcast<'a, I, S>(
+        &'a self,
+        id: impl Into<String>,
+        chat_ids: I,
+        store: S,
+    ) -> Broadcast<'a>
+    where
+        I: IntoIterator<Item = ChatId>,
+        S: BroadcastStore + 'a,
+    {
+        Broadcast {
+            tele: self,
+            id: id.into(),
+            chat_ids: chat_ids.into_iter().collect(),
+            skip: HashSet::new(),
+            store: Box::new(store),
+            limiter: RateLimiter::new(),
+            max_retries: 3,
+            time_budget: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        BroadcastStore, ChatId, Clock, DeliveryFailure, FakeClock, MemoryStore, RateLimiter,
+        ReplayTransport, Telegram,
+    };
+
+    fn telegram(replay: &Arc<ReplayTransport>) -> Telegram {
+        Telegram::new("TOKEN".to_owned()).with_transport(replay.clone())
+    }
+
+    fn sent_to(replay: &ReplayTransport) -> Vec<i64> {
+        let calls = replay.calls().into_iter();
+        calls
+            .map(|(_, body)| body["chat_id"].as_i64().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn classifies_failures() {
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push_error(403, "Forbidden: bot was blocked by the user");
+        replay.push_error(400, "Bad Request: chat not found");
+        replay.push_error(403, "Forbidden: user is deactivated");
+        replay.push_error(403, "Forbidden: bot was kicked from the group chat");
+        replay.push_error(400, "Bad Request: message text is empty");
+
+        let tele = telegram(&replay);
+        let clock = FakeClock::default();
+        let report = tele
+            .broadcast("b", (1..=5).map(ChatId), MemoryStore::new())
+            .rate_limiter(RateLimiter::new().clock(clock))
+            .send("hi")
+            .unwrap();
+
+        assert!(report.is_finished());
+        assert!(report.delivered.is_empty());
+        assert_eq!(
+            report.failed,
+            [
+                (ChatId(1), DeliveryFailure::BlockedByUser),
+                (ChatId(2), DeliveryFailure::ChatNotFound),
+                (ChatId(3), DeliveryFailure::UserDeactivated),
+                (ChatId(4), DeliveryFailure::KickedFromChat),
+                (
+                    ChatId(5),
+                    DeliveryFailure::Other("Bad Request: message text is empty".to_owned())
+                ),
+            ]
+        );
+        assert_eq!(
+            report.unreachable().collect::<Vec<_>>(),
+            [ChatId(1), ChatId(2), ChatId(3), ChatId(4)]
+        );
+    }
+
+    #[test]
+    fn delivers_and_skips() {
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push_result(serde_json::json!({
+            "message_id": 1,
+            "date": 0,
+            "chat": { "id": 1, "type": "private", "first_name": "a" },
+            "text": "hi",
+        }));
+
+        let tele = telegram(&replay);
+        let report = tele
+            .broadcast("b", [ChatId(1), ChatId(2), ChatId(1)], MemoryStore::new())
+            .rate_limiter(RateLimiter::new().clock(FakeClock::default()))
+            .skip([ChatId(2)])
+            .send("hi")
+            .unwrap();
+
+        assert_eq!(report.delivered, [ChatId(1)]);
+        assert_eq!(report.skipped, [ChatId(2), ChatId(1)]);
+        assert_eq!(sent_to(&replay), [1]);
+    }
+
+    #[test]
+    fn resumes_after_time_budget() {
+        let replay = Arc::new(ReplayTransport::default());
+        for _ in 0..3 {
+            replay.push_error(400, "Bad Request: chat not found");
+        }
+
+        let tele = telegram(&replay);
+        let store = MemoryStore::new();
+        let clock = FakeClock::default();
+        // one message per second to the same group makes the budget run out
+        let chats = [ChatId(-1), ChatId(-1), ChatId(-2), ChatId(-3)];
+        let limits = crate::Limits {
+            global: crate::Rate::new(1, Duration::from_secs(1)),
+            ..Default::default()
+        };
+        let broadcast = || {
+            tele.broadcast("b", chats, &store)
+                .rate_limiter(RateLimiter::with_limits(limits).clock(clock.clone()))
+                .time_budget(Duration::from_secs(1))
+        };
+
+        let report = broadcast().send("hi").unwrap();
+        assert_eq!(report.remaining, 1);
+        assert_eq!(store.load("b").unwrap().unwrap().cursor, 3);
+        assert_eq!(sent_to(&replay), [-1, -2]);
+
+        let report = broadcast().send("hi").unwrap();
+        assert!(report.is_finished());
+        assert_eq!(report.failed.len(), 3);
+        assert_eq!(report.skipped, [ChatId(-1)]);
+        assert_eq!(sent_to(&replay), [-1, -2, -3]);
+    }
+
+    #[test]
+    fn waits_out_flood_control() {
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push(crate::HttpResponse::new(
+            429,
+            r#"{"ok":false,"error_code":429,"description":"Too Many Requests: retry after 3","parameters":{"retry_after":3}}"#,
+        ));
+        replay.push_error(403, "Forbidden: bot was blocked by the user");
+
+        let tele = telegram(&replay);
+        let clock = FakeClock::default();
+        let report = tele
+            .broadcast("b", [ChatId(1)], MemoryStore::new())
+            .rate_limiter(RateLimiter::new().clock(clock.clone()))
+            .send("hi")
+            .unwrap();
+
+        assert_eq!(report.failed, [(ChatId(1), DeliveryFailure::BlockedByUser)]);
+        assert_eq!(clock.now(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn fails_after_max_retries() {
+        let replay = Arc::new(ReplayTransport::default());
+        let too_many = r#"{"ok":false,"error_code":429,"description":"Too Many Requests: retry after 3","parameters":{"retry_after":3}}"#;
+        for _ in 0..3 {
+            replay.push(crate::HttpResponse::new(429, too_many));
+        }
+        replay.push_error(403, "Forbidden: bot was blocked by the user");
+
+        let tele = telegram(&replay);
+        let clock = FakeClock::default();
+        let report = tele
+            .broadcast("b", [ChatId(1), ChatId(2)], MemoryStore::new())
+            .rate_limiter(RateLimiter::new().clock(clock.clone()))
+            .max_retries(2)
+            .send("hi")
+            .unwrap();
+
+        let flood = DeliveryFailure::Other("Too Many Requests: retry after 3".to_owned());
+        assert!(!flood.is_permanent());
+        assert_eq!(
+            report.failed,
+            [
+                (ChatId(1), flood),
+                (ChatId(2), DeliveryFailure::BlockedByUser)
+            ]
+        );
+        assert_eq!(sent_to(&replay), [1, 1, 1, 2]);
+        assert_eq!(clock.now(), Duration::from_secs(6));
+    }
+
+    #[test]
+    fn stops_on_transport_errors() {
+        let replay = Arc::new(ReplayTransport::default());
+        let tele = telegram(&replay);
+        let store = MemoryStore::new();
+
+        // nothing to replay, so sending fails before reaching the Bot API
+        let result = tele
+            .broadcast("b", [ChatId(1), ChatId(2)], &store)
+            .rate_limiter(RateLimiter::new().clock(FakeClock::default()))
+            .send("hi");
+
+        assert!(result.is_err());
+        assert_eq!(store.load("b").unwrap(), None);
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::ResponseParameters;
+
+/// An unsuccessful Bot API response.
+///
+/// Methods of [`Telegram`] return it wrapped in an [`anyhow::Error`], use
+/// [`anyhow::Error::downcast_ref`] to get it back.
+///
+/// [`Telegram`]: crate::Telegram
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ApiError {
+    pub error_code: u16,
+
+    pub description: String,
+
+    /// Describes why the request failed, if Telegram told so.
+    #[serde(default)]
+    pub parameters: Option<ResponseParameters>,
+}
+
+impl ApiError {
+    /// Parses the error out of a Bot API response body, returns `None` if the
+    /// response is not an error.
+    #[must_use]
+    pub fn from_response(body: &[u8]) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Response {
+            ok: bool,
+            #[serde(flatten)]
+            error: Option<ApiError>,
+        }
+
+        match serde_json::from_slice(body) {
+            Ok(Response { ok: false, error }) => error,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tg api error {}: {}", self.error_code, self.description)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{ApiError, ResponseParameters};
+
+    #[test]
+    fn from_response() {
+        let body = br#"{"ok":false,"error_code":429,"description":"Too Many Requests: retry after 5","parameters":{"retry_after":5}}"#;
+
+        assert_eq!(
+            ApiError::from_response(body),
+            Some(ApiError {
+                error_code: 429,
+                description: "Too Many Requests: retry after 5".to_owned(),
+                parameters: Some(ResponseParameters::RetryAfter(Duration::from_secs(5))),
+            })
+        );
+        assert_eq!(
+            ApiError::from_response(br#"{"ok":true,"result":true}"#),
+            None
+        );
+        assert_eq!(ApiError::from_response(b"<html>"), None);
+    }
+}
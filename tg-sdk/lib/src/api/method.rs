@@ -1,9 +1,11 @@
 // TODO: with optional params
 
+use serde::Serialize;
 use strum::Display;
 
-#[derive(Debug, Display)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, Serialize)]
 #[strum(serialize_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
 pub enum Method {
     GetMe,
     LogOut,
@@ -88,6 +90,24 @@ pub enum Method {
     StopPoll,
     DeleteMessage,
     DeleteMessages,
+    SendSticker,
+    GetStickerSet,
+    GetCustomEmojiStickers,
+    UploadStickerFile,
+    CreateNewStickerSet,
+    AddStickerToSet,
+    SetStickerPositionInSet,
+    DeleteStickerFromSet,
+    SetStickerSetThumb,
+    AnswerWebAppQuery,
+    SendInvoice,
+    CreateInvoiceLink,
+    AnswerShippingQuery,
+    AnswerPreCheckoutQuery,
+    SetPassportDataErrors,
+    SendGame,
+    SetGameScore,
+    GetGameHighScores,
 }
 
 #[cfg(test)]
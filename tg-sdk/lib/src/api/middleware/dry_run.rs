@@ -0,0 +1,83 @@
+use std::sync::Mutex;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::{ApiCall, HttpResponse, Middleware, Next};
+
+/// A middleware that records calls instead of sending them.
+///
+/// Every call is answered with a successful response carrying
+/// [`DryRun::result`] (`true` by default), so methods that return something
+/// else fail to decode it unless a matching result is set.
+#[derive(Debug)]
+pub struct DryRun {
+    calls: Mutex<Vec<ApiCall>>,
+    result: Value,
+}
+
+impl DryRun {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            calls: Mutex::new(Vec::new()),
+            result: Value::Bool(true),
+        }
+    }
+
+    /// Sets the `result` every call is answered with.
+    #[must_use]
+    pub fn result(mut self, result: Value) -> Self {
+        self.result = result;
+        self
+    }
+
+    /// Returns the calls recorded so far, oldest first.
+    #[must_use]
+    pub fn calls(&self) -> Vec<ApiCall> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl Default for DryRun {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Middleware for DryRun {
+    fn handle(&self, call: ApiCall, _: Next<'_>) -> Result<HttpResponse> {
+        self.calls.lock().unwrap().push(call);
+
+        let body = serde_json::json!({ "ok": true, "result": self.result });
+        Ok(HttpResponse::new(200, body.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{ChatId, DryRun, Method, ReplayTransport, Telegram};
+
+    #[test]
+    fn records_instead_of_sending() {
+        let dry_run = Arc::new(DryRun::new());
+        let replay = Arc::new(ReplayTransport::default());
+
+        let tele = Telegram::new("TOKEN".to_owned())
+            .with_transport(replay.clone())
+            .with_middleware(dry_run.clone());
+
+        assert!(tele.set_chat_title(ChatId(-1), "title".to_owned()).is_ok());
+        // `true` is not a `Message`
+        assert!(tele.send_message(ChatId(-1), "hi").is_err());
+
+        let calls = dry_run.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].method, Method::SetChatTitle);
+        assert_eq!(calls[1].method, Method::SendMessage);
+        assert_eq!(calls[1].chat_id(), Some(ChatId(-1)));
+        assert!(replay.requests().is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Serialize, Serializer};
+
+use crate::{ApiCall, ApiError, ChatId, HttpResponse, Method, Middleware, Next};
+
+/// A structured log entry of a single Bot API call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LogRecord {
+    pub method: Method,
+
+    pub chat_id: Option<ChatId>,
+
+    /// Request url with the bot token redacted.
+    pub url: String,
+
+    #[serde(rename = "latency_ms", serialize_with = "serialize_millis")]
+    pub latency: Duration,
+
+    /// HTTP status, `None` if no response was received.
+    pub status: Option<u16>,
+
+    /// `error_code` of a failed Bot API response.
+    pub error_code: Option<u16>,
+
+    /// Description of the failure, if any.
+    pub error: Option<String>,
+}
+
+impl LogRecord {
+    /// Returns `true` if the call didn't succeed.
+    #[must_use]
+    pub fn is_failure(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// A middleware that logs every call as a [`LogRecord`].
+///
+/// By default records are written as JSON: failed calls with
+/// [`flowsnet_platform_sdk::write_error_log`] on wasi and [`log::error!`]
+/// elsewhere, the others with [`log::info!`] so they only show up when the
+/// application installs a logger. Use [`Logger::with_sink`] to send records
+/// elsewhere.
+pub struct Logger {
+    sink: Box<dyn Fn(&LogRecord) + Send + Sync>,
+}
+
+impl Logger {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_sink(write_flows_log)
+    }
+
+    #[must_use]
+    pub fn with_sink<F>(sink: F) -> Self
+    where
+        F: Fn(&LogRecord) + Send + Sync + 'static,
+    {
+        Self {
+            sink: Box::new(sink),
+        }
+    }
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Middleware for Logger {
+    fn handle(&self, call: ApiCall, next: Next<'_>) -> Result<HttpResponse> {
+        let method = call.method;
+        let chat_id = call.chat_id();
+        let url = redact_token(&call.request.url);
+        // Transport errors often quote the url, token included.
+        let token = token_of(&call.request.url).map(str::to_owned);
+
+        let start = Instant::now();
+        let response = next.run(call);
+        let latency = start.elapsed();
+
+        let (status, error_code, error) = match &response {
+            Ok(response) => match ApiError::from_response(&response.body) {
+                Some(e) => (
+                    Some(response.status),
+                    Some(e.error_code),
+                    Some(e.description),
+                ),
+                // e.g. an HTML error page from a proxy in front of the Bot API
+                None if !(200..300).contains(&response.status) => {
+                    (Some(response.status), None, Some(http_error(response)))
+                }
+                None => (Some(response.status), None, None),
+            },
+            Err(e) => {
+                let error = e.to_string();
+                let error = match &token {
+                    Some(token) => error.replace(token.as_str(), "<redacted>"),
+                    None => error,
+                };
+                (None, None, Some(error))
+            }
+        };
+
+        (self.sink)(&LogRecord {
+            method,
+            chat_id,
+            url,
+            latency,
+            status,
+            error_code,
+            error,
+        });
+
+        response
+    }
+}
+
+/// Replaces the bot token in a Bot API url with `<redacted>`.
+///
+/// ```
+/// use tg_flows::redact_token;
+///
+/// assert_eq!(
+///     redact_token("https://api.telegram.org/bot123:ABC/getMe"),
+///     "https://api.telegram.org/bot<redacted>/getMe",
+/// );
+/// ```
+#[must_use]
+pub fn redact_token(url: &str) -> String {
+    url.split('/')
+        .map(|segment| match segment.strip_prefix("bot") {
+            Some(token) if token.contains(':') => "bot<redacted>",
+            _ => segment,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Describes a failed response that isn't a Bot API error by its status and
+/// body.
+fn http_error(response: &HttpResponse) -> String {
+    let body = String::from_utf8_lossy(&response.body);
+    match body.trim() {
+        "" => format!("HTTP {}", response.status),
+        body => format!("HTTP {}: {body}", response.status),
+    }
+}
+
+/// Returns the bot token in a Bot API url, if any.
+fn token_of(url: &str) -> Option<&str> {
+    url.split('/')
+        .filter_map(|segment| segment.strip_prefix("bot"))
+        .find(|token| token.contains(':'))
+}
+
+fn write_flows_log(record: &LogRecord) {
+    let line = serde_json::to_string(record).unwrap_or_default();
+
+    if record.is_failure() {
+        #[cfg(target_os = "wasi")]
+        {
+            flowsnet_platform_sdk::write_error_log!(line);
+        }
+        #[cfg(not(target_os = "wasi"))]
+        log::error!("{line}");
+    } else {
+        log::info!("{line}");
+    }
+}
+
+fn serialize_millis<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_u128(duration.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{redact_token, ChatId, HttpResponse, Logger, Method, ReplayTransport, Telegram};
+
+    #[test]
+    fn redacts_token() {
+        assert_eq!(
+            redact_token("https://api.telegram.org/bot123456:AAE-x_y/sendMessage"),
+            "https://api.telegram.org/bot<redacted>/sendMessage"
+        );
+        assert_eq!(
+            redact_token("https://example.com/bot/sendMessage"),
+            "https://example.com/bot/sendMessage"
+        );
+    }
+
+    #[test]
+    fn logs_calls() {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = records.clone();
+
+        let replay = ReplayTransport::default();
+        replay.push_result(serde_json::json!(true));
+        replay.push_error(403, "Forbidden: bot was blocked by the user");
+
+        let tele = Telegram::new("123:SECRET".to_owned())
+            .with_transport(replay)
+            .with_middleware(Logger::with_sink(move |r| {
+                sink.lock().unwrap().push(r.clone())
+            }));
+
+        assert!(tele.leave_chat(ChatId(-5)).is_ok());
+        assert!(tele.leave_chat(ChatId(7)).is_err());
+
+        let records = records.lock().unwrap();
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(records[0].method, Method::LeaveChat);
+        assert_eq!(records[0].chat_id, Some(ChatId(-5)));
+        assert_eq!(
+            records[0].url,
+            "https://api.telegram.org/bot<redacted>/leaveChat"
+        );
+        assert_eq!(records[0].status, Some(200));
+        assert!(!records[0].is_failure());
+
+        assert_eq!(records[1].chat_id, Some(ChatId(7)));
+        assert_eq!(records[1].error_code, Some(403));
+        assert!(records[1].is_failure());
+
+        let json = serde_json::to_value(&records[1]).unwrap();
+        assert_eq!(json["method"], "leaveChat");
+        assert!(json["latency_ms"].is_u64());
+        assert!(!json.to_string().contains("SECRET"));
+    }
+
+    #[test]
+    fn logs_http_errors() {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = records.clone();
+
+        let replay = ReplayTransport::default();
+        replay.push(HttpResponse::new(502, "<html>Bad Gateway</html>"));
+
+        let tele = Telegram::new("123:SECRET".to_owned())
+            .with_transport(replay)
+            .with_middleware(Logger::with_sink(move |r| {
+                sink.lock().unwrap().push(r.clone())
+            }));
+        assert!(tele.leave_chat(ChatId(7)).is_err());
+
+        let records = records.lock().unwrap();
+        assert_eq!(records[0].status, Some(502));
+        assert_eq!(records[0].error_code, None);
+        assert_eq!(
+            records[0].error.as_deref(),
+            Some("HTTP 502: <html>Bad Gateway</html>")
+        );
+        assert!(records[0].is_failure());
+    }
+
+    #[test]
+    fn redacts_transport_errors() {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = records.clone();
+
+        // no response queued, so the transport fails quoting the url
+        let tele = Telegram::new("123:SECRET".to_owned())
+            .with_transport(ReplayTransport::default())
+            .with_middleware(Logger::with_sink(move |r| {
+                sink.lock().unwrap().push(r.clone())
+            }));
+        assert!(tele
+            .leave_chat(ChatId(7))
+            .unwrap_err()
+            .to_string()
+            .contains("SECRET"));
+
+        let records = records.lock().unwrap();
+        let error = records[0].error.as_deref().unwrap();
+        assert!(!error.contains("SECRET"));
+        assert!(error.contains("<redacted>"));
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::{ApiCall, ApiError, HttpResponse, Method, Middleware, Next};
+
+/// Counters of the calls made to one [`Method`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MethodStats {
+    pub calls: u64,
+
+    /// Calls that failed, either in the transport or with a Bot API error.
+    pub errors: u64,
+
+    /// Time spent in all calls.
+    pub latency: Duration,
+}
+
+/// A middleware that counts calls per [`Method`].
+///
+/// Keep it in an [`Arc`](std::sync::Arc) to read the counters after adding it
+/// to a [`Telegram`](crate::Telegram).
+#[derive(Debug, Default)]
+pub struct Metrics {
+    stats: Mutex<HashMap<Method, MethodStats>>,
+}
+
+impl Metrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the counters of `method`.
+    #[must_use]
+    pub fn get(&self, method: Method) -> MethodStats {
+        self.stats
+            .lock()
+            .unwrap()
+            .get(&method)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the counters of every method called so far.
+    #[must_use]
+    pub fn snapshot(&self) -> HashMap<Method, MethodStats> {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+impl Middleware for Metrics {
+    fn handle(&self, call: ApiCall, next: Next<'_>) -> Result<HttpResponse> {
+        let method = call.method;
+
+        let start = Instant::now();
+        let response = next.run(call);
+        let latency = start.elapsed();
+
+        let failed = match &response {
+            Ok(response) => ApiError::from_response(&response.body).is_some(),
+            Err(_) => true,
+        };
+
+        let mut stats = self.stats.lock().unwrap();
+        let stats = stats.entry(method).or_default();
+        stats.calls += 1;
+        stats.errors += u64::from(failed);
+        stats.latency += latency;
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{ChatId, Method, Metrics, ReplayTransport, Telegram};
+
+    #[test]
+    fn counts_per_method() {
+        let metrics = Arc::new(Metrics::new());
+
+        let replay = ReplayTransport::default();
+        replay.push_result(serde_json::json!(true));
+        replay.push_error(400, "Bad Request: chat not found");
+        replay.push_result(serde_json::json!(3));
+
+        let tele = Telegram::new("TOKEN".to_owned())
+            .with_transport(replay)
+            .with_middleware(metrics.clone());
+
+        assert!(tele.leave_chat(ChatId(1)).is_ok());
+        assert!(tele.leave_chat(ChatId(2)).is_err());
+        assert_eq!(tele.get_chat_member_count(ChatId(1)).unwrap(), 3);
+        // nothing left to replay
+        assert!(tele.get_chat_member_count(ChatId(1)).is_err());
+
+        let leave_chat = metrics.get(Method::LeaveChat);
+        assert_eq!((leave_chat.calls, leave_chat.errors), (2, 1));
+
+        let member_count = metrics.get(Method::GetChatMemberCount);
+        assert_eq!((member_count.calls, member_count.errors), (2, 1));
+
+        assert_eq!(metrics.get(Method::GetMe).calls, 0);
+        assert_eq!(metrics.snapshot().len(), 2);
+    }
+}
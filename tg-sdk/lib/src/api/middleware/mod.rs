@@ -0,0 +1,140 @@
+//! Hooks around every Bot API call made by [`Telegram`].
+//!
+//! Middlewares are added with [`Telegram::with_middleware`] and run in the
+//! order they were added, each one deciding whether (and how) to pass the call
+//! on to the rest of the chain with [`Next::run`]. The last link of the chain
+//! is the [`Transport`].
+//!
+//! [`Telegram`]: crate::Telegram
+//! [`Telegram::with_middleware`]: crate::Telegram::with_middleware
+
+mod dry_run;
+mod log;
+mod metrics;
+mod rate_limit;
+
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::{ChatId, HttpRequest, HttpResponse, Method, Transport};
+
+pub use self::{
+    dry_run::DryRun,
+    log::{redact_token, LogRecord, Logger},
+    metrics::{MethodStats, Metrics},
+    rate_limit::{Clock, Limits, Rate, RateLimiter, SystemClock},
+};
+
+#[cfg(test)]
+pub(crate) use self::rate_limit::FakeClock;
+
+/// A Bot API call on its way to the [`Transport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiCall {
+    pub method: Method,
+    pub request: HttpRequest,
+}
+
+impl ApiCall {
+    /// Returns the `chat_id` this call targets, if it has a JSON body with a
+    /// numeric `chat_id`.
+    #[must_use]
+    pub fn chat_id(&self) -> Option<ChatId> {
+        let body = serde_json::from_slice::<serde_json::Value>(&self.request.body).ok()?;
+        body.get("chat_id")?.as_i64().map(ChatId)
+    }
+}
+
+/// Behavior around Bot API calls, e.g. logging or metrics.
+pub trait Middleware: Send + Sync {
+    /// Handles `call`, usually by passing it on with `next.run(call)`.
+    fn handle(&self, call: ApiCall, next: Next<'_>) -> Result<HttpResponse>;
+}
+
+impl<M> Middleware for Arc<M>
+where
+    M: Middleware + ?Sized,
+{
+    fn handle(&self, call: ApiCall, next: Next<'_>) -> Result<HttpResponse> {
+        (**self).handle(call, next)
+    }
+}
+
+/// The rest of a middleware chain.
+///
+/// It is `Copy`, so a middleware can run the rest of the chain more than once,
+/// e.g. to retry a call.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    middlewares: &'a [Box<dyn Middleware>],
+    transport: &'a dyn Transport,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        middlewares: &'a [Box<dyn Middleware>],
+        transport: &'a dyn Transport,
+    ) -> Self {
+        Self {
+            middlewares,
+            transport,
+        }
+    }
+
+    /// Runs the remaining middlewares and finally sends the request.
+    pub fn run(self, call: ApiCall) -> Result<HttpResponse> {
+        match self.middlewares.split_first() {
+            Some((middleware, rest)) => middleware.handle(call, Next::new(rest, self.transport)),
+            None => self.transport.send(call.request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::Result;
+
+    use crate::{ApiCall, ChatId, HttpResponse, Middleware, Next, ReplayTransport, Telegram};
+
+    struct Tag(&'static str, Arc<Mutex<Vec<&'static str>>>);
+
+    impl Middleware for Tag {
+        fn handle(&self, call: ApiCall, next: Next<'_>) -> Result<HttpResponse> {
+            self.1.lock().unwrap().push(self.0);
+            next.run(call)
+        }
+    }
+
+    #[test]
+    fn runs_in_order() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let replay = ReplayTransport::default();
+        replay.push_result(serde_json::json!(true));
+
+        let tele = Telegram::new("TOKEN".to_owned())
+            .with_transport(replay)
+            .with_middleware(Tag("outer", seen.clone()))
+            .with_middleware(Tag("inner", seen.clone()));
+
+        assert!(tele.leave_chat(ChatId(-1)).is_ok());
+        assert_eq!(*seen.lock().unwrap(), ["outer", "inner"]);
+    }
+
+    #[test]
+    fn chat_id() {
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push_result(serde_json::json!(true));
+
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+        assert!(tele.leave_chat(ChatId(-100)).is_ok());
+
+        let call = ApiCall {
+            method: crate::Method::LeaveChat,
+            request: replay.requests().remove(0),
+        };
+        assert_eq!(call.chat_id(), Some(ChatId(-100)));
+    }
+}
@@ -0,0 +1,467 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::{
+    ApiCall, ApiError, ChatId, HttpResponse, Method, Middleware, Next, ResponseParameters,
+};
+
+/// A source of time for [`RateLimiter`], replaceable in tests.
+pub trait Clock: Send + Sync {
+    /// Time elapsed since an arbitrary, fixed origin.
+    fn now(&self) -> Duration;
+
+    fn sleep(&self, duration: Duration);
+}
+
+/// The real clock.
+#[derive(Clone, Copy, Debug)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// A clock for tests that only moves when slept on.
+#[cfg(test)]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FakeClock(std::sync::Arc<Mutex<Duration>>);
+
+#[cfg(test)]
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        *self.0.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        *self.0.lock().unwrap() += duration;
+    }
+}
+
+/// At most `count` messages per `period`, or no limit if `count` is 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rate {
+    pub count: usize,
+    pub period: Duration,
+}
+
+impl Rate {
+    #[must_use]
+    pub const fn new(count: usize, period: Duration) -> Self {
+        Self { count, period }
+    }
+}
+
+/// Limits enforced by [`RateLimiter`].
+///
+/// The defaults follow [Telegram's FAQ]: 30 messages per second overall, one
+/// message per second in a private chat and 20 messages per minute in a group.
+///
+/// [Telegram's FAQ]: https://core.telegram.org/bots/faq#my-bot-is-hitting-limits-how-do-i-avoid-this
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    pub global: Rate,
+
+    /// Limit per chat with a user.
+    pub private_chat: Rate,
+
+    /// Limit per group, supergroup or channel.
+    pub group: Rate,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            global: Rate::new(30, Duration::from_secs(1)),
+            private_chat: Rate::new(1, Duration::from_secs(1)),
+            group: Rate::new(20, Duration::from_secs(60)),
+        }
+    }
+}
+
+/// A middleware that delays outgoing messages to stay within Telegram's
+/// [`Limits`].
+///
+/// Only methods that send messages (`send*`, `forwardMessage` and
+/// `copyMessage`) are limited, in the order they were made. Calls wait instead
+/// of failing, and a call that still hits flood control (HTTP 429) is retried
+/// after the `retry_after` Telegram asks for.
+pub struct RateLimiter {
+    limits: Limits,
+    max_retries: usize,
+    clock: Box<dyn Clock>,
+    state: Mutex<State>,
+    queued: AtomicUsize,
+}
+
+#[derive(Default)]
+struct State {
+    /// Reserved send times, in increasing order.
+    global: VecDeque<Duration>,
+    chats: HashMap<ChatId, VecDeque<Duration>>,
+}
+
+/// Number of chats tracked before stale ones are dropped.
+const CHATS_PRUNE_THRESHOLD: usize = 1024;
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_limits(Limits::default())
+    }
+
+    #[must_use]
+    pub fn with_limits(limits: Limits) -> Self {
+        Self {
+            limits,
+            max_retries: 3,
+            clock: Box::new(SystemClock::new()),
+            state: Mutex::new(State::default()),
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Uses `clock` to measure and wait.
+    #[must_use]
+    pub fn clock<C>(mut self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Sets how many times a call rejected with HTTP 429 is retried, 3 by
+    /// default.
+    #[must_use]
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Returns the number of calls currently waiting for their turn.
+    #[must_use]
+    pub fn queue_depth(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// Blocks until a message to `chat_id` may be sent.
+    pub(crate) fn wait(&self, chat_id: Option<ChatId>) {
+        let at = self.reserve(chat_id);
+        let wait = at.saturating_sub(self.clock.now());
+        if !wait.is_zero() {
+            self.sleep(wait);
+        }
+    }
+
+    pub(crate) fn now(&self) -> Duration {
+        self.clock.now()
+    }
+
+    /// Sleeps on the clock, counting as a queued call meanwhile.
+    pub(crate) fn sleep(&self, duration: Duration) {
+        self.queued.fetch_add(1, Ordering::SeqCst);
+        self.clock.sleep(duration);
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Reserves the earliest time a message to `chat_id` may be sent at.
+    fn reserve(&self, chat_id: Option<ChatId>) -> Duration {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let State { global, chats } = &mut *state;
+
+        if chats.len() > CHATS_PRUNE_THRESHOLD {
+            let period = self
+                .limits
+                .private_chat
+                .period
+                .max(self.limits.group.period);
+            chats.retain(|_, times| times.back().is_some_and(|&t| t + period > now));
+        }
+
+        let mut at = global.back().map_or(now, |&last| last.max(now));
+        at = at.max(ready_at(global, self.limits.global, at));
+
+        let chat = chat_id.map(|id| {
+            let rate = match id.is_user() {
+                true => self.limits.private_chat,
+                false => self.limits.group,
+            };
+            (chats.entry(id).or_default(), rate)
+        });
+        if let Some((times, rate)) = &chat {
+            at = at.max(ready_at(times, *rate, at));
+        }
+
+        push(global, self.limits.global, at);
+        if let Some((times, rate)) = chat {
+            push(times, rate, at);
+        }
+
+        at
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Middleware for RateLimiter {
+    fn handle(&self, call: ApiCall, next: Next<'_>) -> Result<HttpResponse> {
+        if !sends_message(call.method) {
+            return next.run(call);
+        }
+
+        let chat_id = call.chat_id();
+        let mut retries = 0;
+        loop {
+            self.wait(chat_id);
+
+            let response = next.run(call.clone())?;
+            let retry_after = match ApiError::from_response(&response.body) {
+                Some(ApiError {
+                    parameters: Some(ResponseParameters::RetryAfter(retry_after)),
+                    ..
+                }) => retry_after,
+                _ => return Ok(response),
+            };
+
+            if retries == self.max_retries {
+                return Ok(response);
+            }
+            retries += 1;
+            self.sleep(retry_after);
+        }
+    }
+}
+
+/// Returns the earliest time not before `at` when one more message fits into
+/// `rate`, given the sorted send `times`.
+fn ready_at(times: &VecDeque<Duration>, rate: Rate, at: Duration) -> Duration {
+    if rate.count == 0 {
+        return at;
+    }
+    let in_window = times.iter().rev().take_while(|&&t| t + rate.period > at);
+    match in_window.count() < rate.count {
+        true => at,
+        false => times[times.len() - rate.count] + rate.period,
+    }
+}
+
+/// Records a send at `at`, forgetting times that can't matter anymore.
+fn push(times: &mut VecDeque<Duration>, rate: Rate, at: Duration) {
+    times.push_back(at);
+    while times.len() > rate.count {
+        times.pop_front();
+    }
+}
+
+/// Whether `method` sends a message and so counts against the limits.
+fn sends_message(method: Method) -> bool {
+    let name = method.to_string();
+    (name.starts_with("send") && method != Method::SendChatAction)
+        || matches!(method, Method::ForwardMessage | Method::CopyMessage)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{mpsc, Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::{ChatId, Clock, FakeClock, Limits, Rate, RateLimiter, ReplayTransport, Telegram};
+
+    const PRIVATE: ChatId = ChatId(42);
+    const GROUP: ChatId = ChatId(-42);
+
+    fn telegram(clock: &FakeClock, responses: usize) -> Telegram {
+        let replay = ReplayTransport::default();
+        for _ in 0..responses {
+            replay.push_result(serde_json::json!(true));
+        }
+
+        Telegram::new("TOKEN".to_owned())
+            .with_transport(replay)
+            .with_middleware(RateLimiter::new().clock(clock.clone()))
+    }
+
+    /// Sends to each chat in turn, returning the times they were sent at.
+    fn send_times(tele: &Telegram, clock: &FakeClock, chats: &[ChatId]) -> Vec<u64> {
+        chats
+            .iter()
+            .map(|&chat| {
+                // `true` is not a message, but the call was still made
+                let _ = tele.forward_message(chat, ChatId(1));
+                clock.now().as_secs()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn private_chat() {
+        let clock = FakeClock::default();
+        let tele = telegram(&clock, 3);
+
+        assert_eq!(send_times(&tele, &clock, &[PRIVATE; 3]), [0, 1, 2]);
+    }
+
+    #[test]
+    fn group() {
+        let clock = FakeClock::default();
+        let tele = telegram(&clock, 22);
+
+        let times = send_times(&tele, &clock, &[GROUP; 22]);
+        assert_eq!(times[..20], [0; 20]);
+        assert_eq!(times[20..], [60, 60]);
+    }
+
+    #[test]
+    fn global() {
+        let clock = FakeClock::default();
+        let tele = telegram(&clock, 61);
+
+        let chats: Vec<_> = (1..=61).map(ChatId).collect();
+        let times = send_times(&tele, &clock, &chats);
+        assert_eq!(times[..30], [0; 30]);
+        assert_eq!(times[30..60], [1; 30]);
+        assert_eq!(times[60], 2);
+    }
+
+    #[test]
+    fn chats_are_independent() {
+        let clock = FakeClock::default();
+        let tele = telegram(&clock, 4);
+
+        let times = send_times(&tele, &clock, &[PRIVATE, GROUP, ChatId(7), GROUP]);
+        assert_eq!(times, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn zero_count_is_unlimited() {
+        let clock = FakeClock::default();
+        let replay = ReplayTransport::default();
+        for _ in 0..3 {
+            replay.push_result(serde_json::json!(true));
+        }
+        let limits = Limits {
+            private_chat: Rate::new(0, Duration::from_secs(1)),
+            ..Limits::default()
+        };
+        let tele = Telegram::new("TOKEN".to_owned())
+            .with_transport(replay)
+            .with_middleware(RateLimiter::with_limits(limits).clock(clock.clone()));
+
+        assert_eq!(send_times(&tele, &clock, &[PRIVATE; 3]), [0, 0, 0]);
+    }
+
+    #[test]
+    fn other_methods_are_not_limited() {
+        let clock = FakeClock::default();
+        let tele = telegram(&clock, 3);
+
+        for _ in 0..3 {
+            assert!(tele.leave_chat(PRIVATE).is_ok());
+        }
+        assert_eq!(clock.now(), Duration::ZERO);
+    }
+
+    #[test]
+    fn retries_after_flood_control() {
+        let clock = FakeClock::default();
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push(crate::HttpResponse::new(
+            429,
+            r#"{"ok":false,"error_code":429,"description":"Too Many Requests: retry after 7","parameters":{"retry_after":7}}"#,
+        ));
+        replay.push_result(serde_json::json!(true));
+
+        let tele = Telegram::new("TOKEN".to_owned())
+            .with_transport(replay.clone())
+            .with_middleware(RateLimiter::new().clock(clock.clone()));
+
+        let _ = tele.forward_message(GROUP, ChatId(1));
+        assert_eq!(replay.requests().len(), 2);
+        assert_eq!(clock.now(), Duration::from_secs(7));
+    }
+
+    /// A clock whose sleeps block until the test lets them go.
+    struct GateClock(Mutex<mpsc::Receiver<()>>);
+
+    impl Clock for GateClock {
+        fn now(&self) -> Duration {
+            Duration::ZERO
+        }
+
+        fn sleep(&self, _: Duration) {
+            self.0.lock().unwrap().recv().unwrap();
+        }
+    }
+
+    #[test]
+    fn queue_depth() {
+        let (release, gate) = mpsc::channel();
+        let limiter = Arc::new(RateLimiter::new().clock(GateClock(Mutex::new(gate))));
+
+        let replay = ReplayTransport::default();
+        replay.push_result(serde_json::json!(true));
+        replay.push_result(serde_json::json!(true));
+        let tele = Arc::new(
+            Telegram::new("TOKEN".to_owned())
+                .with_transport(replay)
+                .with_middleware(limiter.clone()),
+        );
+
+        // the first message goes out right away, the second one has to wait
+        let _ = tele.forward_message(PRIVATE, ChatId(1));
+        assert_eq!(limiter.queue_depth(), 0);
+
+        let sender = std::thread::spawn({
+            let tele = tele.clone();
+            move || {
+                let _ = tele.forward_message(PRIVATE, ChatId(1));
+            }
+        });
+        while limiter.queue_depth() == 0 {
+            std::thread::yield_now();
+        }
+        assert_eq!(limiter.queue_depth(), 1);
+
+        release.send(()).unwrap();
+        sender.join().unwrap();
+        assert_eq!(limiter.queue_depth(), 0);
+    }
+}
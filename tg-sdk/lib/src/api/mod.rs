@@ -1,36 +1,91 @@
+#[cfg(feature = "async")]
+mod async_telegram;
+mod broadcast;
+mod error;
 mod method;
-
-use std::collections::HashMap;
-
-use anyhow::{anyhow, Context, Result};
-use http_req::{request::Request, uri::Uri};
+mod middleware;
+mod moderation;
+mod multipart;
+mod pagination;
+mod support;
+mod thread;
+mod transport;
+
+use std::{collections::HashMap, io::Write};
+
+use anyhow::{anyhow, bail, Context, Result};
+use bytes::Bytes;
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
+use url::Url;
 
 use crate::{
-    Chat, ChatId, ChatInviteLink, ChatMember, ChatPermissions, File, InputFile, Me, Message,
-    MessageId, ParseMode, True, UserId, UserProfilePhotos,
+    types::InputFileLike, CallbackQuery, Chat, ChatAdministratorRights, ChatId, ChatInviteLink,
+    ChatMember, ChatPermissions, File, ForumTopic, GameHighScore, InlineKeyboardMarkup,
+    InlineQueryResult, InputFile, InputSticker, MaskPosition, Me, Message, MessageId, NewInvoice,
+    ParseMode, PassportElementError, ReplyMarkup, RichText, SentWebAppMessage, ShippingOption,
+    Sticker, StickerSet, StickerType, True, UntilDate, UserId, UserProfilePhotos,
+    MESSAGE_LENGTH_LIMIT,
 };
 
+#[cfg(feature = "async")]
+pub use self::async_telegram::AsyncTelegram;
+pub use self::broadcast::{
+    Broadcast, BroadcastReport, BroadcastState, BroadcastStore, CopyOf, DeliveryFailure,
+    MemoryStore, MessageTemplate,
+};
+pub use self::error::ApiError;
 pub use self::method::Method;
+pub use self::middleware::*;
+pub use self::moderation::{
+    Action, Captcha, MemberState, MemoryModerationStore, ModerationEvent, ModerationStore,
+    Moderator, Violation,
+};
+pub use self::pagination::Paginator;
+pub use self::support::{MemoryTicketStore, SupportDesk, SupportEvent, Ticket, TicketStore};
+pub use self::thread::InThread;
+pub use self::transport::*;
 
 const BASE_URL: &str = "https://api.telegram.org/bot";
 
 pub struct Telegram {
     base_url: &'static str,
     token: String,
+    transport: Box<dyn Transport>,
+    middlewares: Vec<Box<dyn Middleware>>,
 }
 
 impl Telegram {
     pub fn new(token: String) -> Self {
+        Self::new_with_base_url(token, BASE_URL)
+    }
+
+    pub fn new_with_base_url(token: String, base_url: &'static str) -> Self {
         Self {
-            base_url: BASE_URL,
+            base_url,
             token,
+            transport: transport::default_transport(),
+            middlewares: Vec::new(),
         }
     }
 
-    pub fn new_with_base_url(token: String, base_url: &'static str) -> Self {
-        Self { base_url, token }
+    /// Sends requests through `transport` instead of the default one.
+    pub fn with_transport<T>(mut self, transport: T) -> Self
+    where
+        T: Transport + 'static,
+    {
+        self.transport = Box::new(transport);
+        self
+    }
+
+    /// Adds `middleware` to the chain run around every call, after the ones
+    /// added before it.
+    pub fn with_middleware<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware + 'static,
+    {
+        self.middlewares.push(Box::new(middleware));
+        self
     }
 }
 
@@ -39,24 +94,10 @@ impl Telegram {
     where
         T: DeserializeOwned,
     {
-        let url = format!("{}{}/{}", self.base_url, self.token, method.to_string());
-        let uri = Uri::try_from(url.as_str())?;
+        let url = format!("{}{}/{}", self.base_url, self.token, method);
+        let request = HttpRequest::post(url, "application/json", body.to_vec());
 
-        let mut writer = Vec::new();
-        Request::new(&uri)
-            .header("Content-Type", "application/json")
-            .header("Content-Length", &body.len())
-            .body(body)
-            .send(&mut writer)?;
-
-        let value = serde_json::from_str::<Value>(&String::from_utf8(writer)?)?;
-
-        let result = value
-            .get("result")
-            .ok_or(anyhow!("tg api returned without result"))?;
-        let t: T = serde_json::from_value(result.clone())?;
-
-        Ok(t)
+        decode_result(&self.send(method, request)?.body)
     }
 
     pub fn request_with_attach<T, S>(
@@ -69,44 +110,227 @@ impl Telegram {
         T: DeserializeOwned,
         S: Serialize,
     {
-        let mut url = format!("{}{}/{}", self.base_url, self.token, method.to_string());
-        if !query.is_empty() {
-            let mut iter = query.iter();
-            let (k, v) = iter.next().unwrap();
+        let url = attach_url(self.base_url, &self.token, method, query)?;
+        let request = HttpRequest::post(url, "multipart/form-data", body.to_vec());
+
+        decode_result(&self.send(method, request)?.body)
+    }
+
+    /// Sends `fields` along with the `files` they refer to, as
+    /// `multipart/form-data` if any of them has to be uploaded.
+    pub fn request_multipart<T>(
+        &self,
+        method: Method,
+        fields: Value,
+        files: Vec<InputFile>,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let url = format!("{}{}/{}", self.base_url, self.token, method);
+        let (content_type, body) = multipart::encode(&fields, files)?;
+        let request = HttpRequest::post(url, &content_type, body);
+
+        decode_result(&self.send(method, request)?.body)
+    }
+
+    /// Sends `bodies` one after the other, each replying to the message sent
+    /// by the one before.
+    pub fn request_replies(&self, method: Method, bodies: Vec<Value>) -> Result<Vec<Message>> {
+        let mut sent: Vec<Message> = Vec::new();
+        for body in bodies {
+            let body = reply_to(body, sent.last());
+            sent.push(self.request(method, body.to_string().as_bytes())?);
+        }
+        Ok(sent)
+    }
+
+    fn send(&self, method: Method, request: HttpRequest) -> Result<HttpResponse> {
+        let next = Next::new(&self.middlewares, self.transport.as_ref());
+        next.run(ApiCall { method, request })
+    }
+}
+
+/// Builds the url of a multipart request, with `query` appended to it.
+fn attach_url<S>(
+    base_url: &str,
+    token: &str,
+    method: Method,
+    query: HashMap<S, S>,
+) -> Result<String>
+where
+    S: Serialize,
+{
+    let mut url = format!("{base_url}{token}/{method}");
+    if !query.is_empty() {
+        let mut iter = query.iter();
+        let (k, v) = iter.next().unwrap();
+        let k = serde_json::to_string(k).context("query key serialize error")?;
+        let v = serde_json::to_string(v).context("query value serialize error")?;
+
+        url += &format!("?{k}={v}");
+
+        for (k, v) in iter {
             let k = serde_json::to_string(k).context("query key serialize error")?;
             let v = serde_json::to_string(v).context("query value serialize error")?;
+            url += &format!("&{k}={v}");
+        }
+    }
 
-            url += &format!("?{k}={v}");
+    Ok(url)
+}
 
-            for (k, v) in iter {
-                let k = serde_json::to_string(k).context("query key serialize error")?;
-                let v = serde_json::to_string(v).context("query value serialize error")?;
-                url += &format!("&{k}={v}");
-            }
-        }
+/// Adds the fields of `value`, which serializes to an object, to `body`.
+fn flatten_into<T>(body: &mut Value, value: &T)
+where
+    T: Serialize,
+{
+    if let (Value::Object(body), Ok(Value::Object(fields))) = (body, serde_json::to_value(value)) {
+        body.extend(fields);
+    }
+}
+
+/// Makes a JSON `body` reply to `message`, if there is one.
+fn reply_to(mut body: Value, message: Option<&Message>) -> Value {
+    if let (Value::Object(fields), Some(message)) = (&mut body, message) {
+        fields.insert("reply_to_message_id".into(), message.id.0.into());
+    }
+    body
+}
+
+/// Counts the bytes written to `inner`.
+struct CountingWriter<'a, W> {
+    inner: &'a mut W,
+    written: u64,
+}
+
+impl<W> Write for CountingWriter<'_, W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.written += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Takes the files out of `input`, after it was serialized into the fields of
+/// a call.
+fn input_files<I>(mut input: I) -> Vec<InputFile>
+where
+    I: InputFileLike,
+{
+    let mut files = Vec::new();
+    input.move_into(&mut |file| files.push(file));
+    files
+}
 
-        let uri = Uri::try_from(url.as_str())?;
+/// Extracts the `result` field of a Bot API response, or the [`ApiError`] it
+/// failed with.
+fn decode_result<T>(response: &[u8]) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    if let Some(e) = ApiError::from_response(response) {
+        return Err(e.into());
+    }
 
-        let mut writer = Vec::new();
-        Request::new(&uri)
-            .header("Content-Type", "multipart/form-data")
-            .header("Content-Length", &body.len())
-            .body(body)
-            .send(&mut writer)?;
+    let value = serde_json::from_str::<Value>(std::str::from_utf8(response)?)?;
 
-        let value = serde_json::from_str::<Value>(&String::from_utf8(writer)?)?;
+    let result = value
+        .get("result")
+        .ok_or(anyhow!("tg api returned without result"))?;
+    let t: T = serde_json::from_value(result.clone())?;
 
-        let result = value
-            .get("result")
-            .ok_or(anyhow!("tg api returned without result"))?;
-        let t: T = serde_json::from_value(result.clone())?;
+    Ok(t)
+}
 
-        Ok(t)
+/// Builds the url `file` is downloaded from, the base url with `file/` before
+/// its last segment, e.g. `https://api.telegram.org/file/bot<token>/<path>`.
+fn file_url(base_url: &str, token: &str, file: &File) -> String {
+    let (root, last) = base_url.rsplit_once('/').unwrap_or(("", base_url));
+    format!("{root}/file/{last}{token}/{}", file.path)
+}
+
+/// Checks the response to a download of `file`, of which `written` bytes were
+/// received.
+fn check_download(file: &File, response: &HttpResponse, written: u64) -> Result<()> {
+    if !(200..300).contains(&response.status) {
+        if let Some(e) = ApiError::from_response(&response.body) {
+            return Err(e.into());
+        }
+        bail!(
+            "downloading {} failed with status {}",
+            file.path,
+            response.status
+        );
+    }
+    // `u32::MAX` stands for an unknown size
+    if file.size != u32::MAX && written != u64::from(file.size) {
+        bail!(
+            "downloaded {written} bytes of {}, expected {}",
+            file.path,
+            file.size
+        );
     }
+
+    Ok(())
+}
+
+/// Defines the Bot API methods on [`Telegram`] and, with the `async` feature,
+/// on [`AsyncTelegram`].
+///
+/// A method body only builds the payload and ends with `self.request(..)`, so
+/// the same body returns the result on the blocking client and a future of it
+/// on the async one. Methods listed after `in_thread:` are also defined on
+/// [`InThread`], whose `request` adds the `message_thread_id`.
+macro_rules! telegram_api {
+    (@blocking $client:ty; $(
+        $(#[$meta:meta])*
+        pub fn $name:ident $(<$($gen:ident: $bound:path),+>)? (&$self:ident $($params:tt)*) -> Result<$ret:ty>
+        $body:block
+    )*) => {
+        impl $client {
+            $(
+                $(#[$meta])*
+                pub fn $name $(<$($gen: $bound),+>)? (&$self $($params)*) -> Result<$ret>
+                $body
+            )*
+        }
+    };
+    (@async $client:ty; $(
+        $(#[$meta:meta])*
+        pub fn $name:ident $(<$($gen:ident: $bound:path),+>)? (&$self:ident $($params:tt)*) -> Result<$ret:ty>
+        $body:block
+    )*) => {
+        #[cfg(feature = "async")]
+        impl $client {
+            $(
+                $(#[$meta])*
+                pub fn $name $(<$($gen: $bound),+>)? (&$self $($params)*)
+                    -> impl std::future::Future<Output = Result<$ret>> + '_
+                $body
+            )*
+        }
+    };
+    (in_thread: $($methods:tt)*) => {
+        telegram_api! { $($methods)* }
+        telegram_api! { @blocking InThread<'_, Telegram>; $($methods)* }
+        telegram_api! { @async InThread<'_, AsyncTelegram>; $($methods)* }
+    };
+    ($($methods:tt)*) => {
+        telegram_api! { @blocking Telegram; $($methods)* }
+        telegram_api! { @async AsyncTelegram; $($methods)* }
+    };
 }
 
 // TODO: general & meaningful api
-impl Telegram {
+telegram_api! {
     pub fn get_me(&self) -> Result<Me> {
         self.request(Method::GetMe, &[])
     }
@@ -118,28 +342,28 @@ impl Telegram {
     pub fn close(&self) -> Result<True> {
         self.request(Method::Close, &[])
     }
+}
 
-    pub fn send_message<T>(&self, chat_id: ChatId, text: T) -> Result<Message>
-    where
-        T: Into<String>,
-    {
-        let text: String = text.into();
-        let body = serde_json::json!({
+// Methods sending messages, also available on `InThread` to send them into a
+// forum topic.
+telegram_api! {
+    in_thread:
+    /// Sends plain text, or [`RichText`] with its formatting.
+    pub fn send_message<T: Into<RichText>>(&self, chat_id: ChatId, text: T) -> Result<Message> {
+        let text: RichText = text.into();
+        let mut body = serde_json::json!({
             "chat_id": chat_id,
-            "text": text,
         });
+        flatten_into(&mut body, &text);
         self.request(Method::SendMessage, body.to_string().as_bytes())
     }
 
-    pub fn send_message_with_parse_mode<T>(
+    pub fn send_message_with_parse_mode<T: Into<String>>(
         &self,
         chat_id: ChatId,
         text: T,
         parse_mode: ParseMode,
-    ) -> Result<Message>
-    where
-        T: Into<String>,
-    {
+    ) -> Result<Message> {
         let text: String = text.into();
         let body = serde_json::json!({
             "chat_id": chat_id,
@@ -149,6 +373,23 @@ impl Telegram {
         self.request(Method::SendMessage, body.to_string().as_bytes())
     }
 
+    /// Sends a message with a keyboard, e.g.
+    /// [`ReplyMarkup::inline_kb`] for buttons under the message.
+    pub fn send_message_with_reply_markup<T: Into<RichText>>(
+        &self,
+        chat_id: ChatId,
+        text: T,
+        reply_markup: ReplyMarkup,
+    ) -> Result<Message> {
+        let text: RichText = text.into();
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "reply_markup": reply_markup,
+        });
+        flatten_into(&mut body, &text);
+        self.request(Method::SendMessage, body.to_string().as_bytes())
+    }
+
     pub fn forward_message(&self, chat_id: ChatId, from_chat_id: ChatId) -> Result<Message> {
         let body = serde_json::json!({
             "chat_id": chat_id,
@@ -157,16 +398,18 @@ impl Telegram {
         self.request(Method::ForwardMessage, body.to_string().as_bytes())
     }
 
+    /// Copies a message without a link to the original, returning the id of
+    /// the copy.
     pub fn copy_message(
         &self,
         chat_id: ChatId,
         from_chat_id: ChatId,
         message_id: MessageId,
-    ) -> Result<Message> {
+    ) -> Result<MessageId> {
         let body = serde_json::json!({
             "chat_id": chat_id,
             "from_chat_id": from_chat_id,
-            "message_id": message_id,
+            "message_id": message_id.0,
         });
         self.request(Method::CopyMessage, body.to_string().as_bytes())
     }
@@ -328,6 +571,58 @@ impl Telegram {
         self.request(Method::SendChatAction, body.to_string().as_bytes())
     }
 
+    /// Sends a sticker by file id or url, or uploads a new `.webp`, `.tgs` or
+    /// `.webm` one.
+    pub fn send_sticker(&self, chat_id: ChatId, sticker: InputFile) -> Result<Message> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "sticker": sticker,
+        });
+        self.request_multipart(Method::SendSticker, body, vec![sticker])
+    }
+
+    /// Sends an invoice, amounts in `invoice` are in the smallest units of its
+    /// currency.
+    pub fn send_invoice(&self, chat_id: ChatId, invoice: NewInvoice) -> Result<Message> {
+        let mut body = serde_json::to_value(invoice).expect("invoice is serializable");
+        body["chat_id"] = serde_json::json!(chat_id);
+        self.request(Method::SendInvoice, body.to_string().as_bytes())
+    }
+
+    /// Sends a game created with [@BotFather], by its short name.
+    ///
+    /// [@BotFather]: https://t.me/botfather
+    pub fn send_game<S: Into<String>>(&self, chat_id: ChatId, game_short_name: S) -> Result<Message> {
+        let game_short_name: String = game_short_name.into();
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "game_short_name": game_short_name,
+        });
+        self.request(Method::SendGame, body.to_string().as_bytes())
+    }
+
+    /// Sends `text` split into messages within [`MESSAGE_LENGTH_LIMIT`], see
+    /// [`RichText::split`], each replying to the one before.
+    pub fn send_long_message<T: Into<RichText>>(
+        &self,
+        chat_id: ChatId,
+        text: T,
+    ) -> Result<Vec<Message>> {
+        let text: RichText = text.into();
+        let parts = text.split(MESSAGE_LENGTH_LIMIT).into_iter();
+        let bodies = parts.map(|part| {
+            let mut body = serde_json::json!({
+                "chat_id": chat_id,
+                "allow_sending_without_reply": true,
+            });
+            flatten_into(&mut body, &part);
+            body
+        });
+        self.request_replies(Method::SendMessage, bodies.collect())
+    }
+}
+
+telegram_api! {
     pub fn get_user_profile_photos(&self, user_id: UserId) -> Result<UserProfilePhotos> {
         let body = serde_json::json!({
             "user_id": user_id,
@@ -350,6 +645,25 @@ impl Telegram {
         self.request(Method::BanChatMember, body.to_string().as_bytes())
     }
 
+    /// Bans a user until `until_date`, a ban for less than 30 seconds or more
+    /// than 366 days is forever. With `revoke_messages` all messages from the
+    /// user are deleted, they always are in supergroups and channels.
+    pub fn ban_chat_member_until(
+        &self,
+        chat_id: ChatId,
+        user_id: UserId,
+        until_date: UntilDate,
+        revoke_messages: bool,
+    ) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "user_id": user_id,
+            "until_date": until_date,
+            "revoke_messages": revoke_messages,
+        });
+        self.request(Method::BanChatMember, body.to_string().as_bytes())
+    }
+
     pub fn unban_chat_member(&self, chat_id: ChatId, user_id: UserId) -> Result<True> {
         let body = serde_json::json!({
             "chat_id": chat_id,
@@ -372,11 +686,43 @@ impl Telegram {
         self.request(Method::RestrictChatMember, body.to_string().as_bytes())
     }
 
-    pub fn promote_chat_member(&self, chat_id: ChatId, user_id: UserId) -> Result<True> {
+    /// Restricts a user until `until_date`, e.g. [`UntilDate::after`] ten
+    /// minutes to mute them for that long. A restriction for less than 30
+    /// seconds or more than 366 days is forever.
+    ///
+    /// Unless `use_independent_chat_permissions` is set, some permissions
+    /// imply others, e.g. sending media implies sending messages.
+    pub fn restrict_chat_member_until(
+        &self,
+        chat_id: ChatId,
+        user_id: UserId,
+        permissions: ChatPermissions,
+        until_date: UntilDate,
+        use_independent_chat_permissions: bool,
+    ) -> Result<True> {
         let body = serde_json::json!({
             "chat_id": chat_id,
             "user_id": user_id,
+            "permissions": permissions,
+            "until_date": until_date,
+            "use_independent_chat_permissions": use_independent_chat_permissions,
         });
+        self.request(Method::RestrictChatMember, body.to_string().as_bytes())
+    }
+
+    /// Gives a user `rights` in a supergroup or channel, the bot must have
+    /// them too. Promoting with no rights, the [`Default`], demotes.
+    pub fn promote_chat_member(
+        &self,
+        chat_id: ChatId,
+        user_id: UserId,
+        rights: ChatAdministratorRights,
+    ) -> Result<True> {
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "user_id": user_id,
+        });
+        flatten_into(&mut body, &rights);
         self.request(Method::PromoteChatMember, body.to_string().as_bytes())
     }
 
@@ -397,7 +743,7 @@ impl Telegram {
         )
     }
 
-    pub fn ban_chat_sender_chat(&self, chat_id: ChatId, sender_chat_id: i32) -> Result<True> {
+    pub fn ban_chat_sender_chat(&self, chat_id: ChatId, sender_chat_id: ChatId) -> Result<True> {
         let body = serde_json::json!({
             "chat_id": chat_id,
             "sender_chat_id": sender_chat_id,
@@ -405,7 +751,7 @@ impl Telegram {
         self.request(Method::BanChatSenderChat, body.to_string().as_bytes())
     }
 
-    pub fn unban_chat_sender_chat(&self, chat_id: ChatId, sender_chat_id: i32) -> Result<True> {
+    pub fn unban_chat_sender_chat(&self, chat_id: ChatId, sender_chat_id: ChatId) -> Result<True> {
         let body = serde_json::json!({
             "chat_id": chat_id,
             "sender_chat_id": sender_chat_id,
@@ -571,36 +917,238 @@ impl Telegram {
         self.request(Method::GetChatMember, body.to_string().as_bytes())
     }
 
-    // TODO: https://core.telegram.org/bots/api#setchatstickerset
+    /// Sets the group sticker set of a supergroup, check
+    /// [`Chat::can_set_sticker_set`] first.
+    pub fn set_chat_sticker_set<N: Into<String>>(
+        &self,
+        chat_id: ChatId,
+        sticker_set_name: N,
+    ) -> Result<True> {
+        let sticker_set_name: String = sticker_set_name.into();
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "sticker_set_name": sticker_set_name,
+        });
+        self.request(Method::SetChatStickerSet, body.to_string().as_bytes())
+    }
+
+    pub fn delete_chat_sticker_set(&self, chat_id: ChatId) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+        });
+        self.request(Method::DeleteChatStickerSet, body.to_string().as_bytes())
+    }
+
+    /// Returns the custom emoji stickers any user can use as a forum topic
+    /// icon.
+    pub fn get_forum_topic_icon_stickers(&self) -> Result<Vec<Sticker>> {
+        self.request(Method::GetForumTopicIconStickers, &[])
+    }
 
-    pub fn edit_message_text<T>(
+    /// Creates a topic in a forum supergroup, send messages into it with
+    /// [`in_thread`](Self::in_thread).
+    ///
+    /// `icon_color` is one of `0x6FB9F0`, `0xFFD67E`, `0xCB86DB`, `0x8EEE98`,
+    /// `0xFF93B2` or `0xFB6F5F` as RGB bytes, `icon_custom_emoji_id` is one of
+    /// [`get_forum_topic_icon_stickers`](Self::get_forum_topic_icon_stickers).
+    pub fn create_forum_topic<N: Into<String>>(
         &self,
         chat_id: ChatId,
-        message_id: MessageId,
+        name: N,
+        icon_color: Option<[u8; 3]>,
+        icon_custom_emoji_id: Option<String>,
+    ) -> Result<ForumTopic> {
+        let name: String = name.into();
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "name": name,
+        });
+        if let Some([r, g, b]) = icon_color {
+            body["icon_color"] = serde_json::json!(u32::from_be_bytes([0, r, g, b]));
+        }
+        if let Some(icon_custom_emoji_id) = icon_custom_emoji_id {
+            body["icon_custom_emoji_id"] = serde_json::json!(icon_custom_emoji_id);
+        }
+        self.request(Method::CreateForumTopic, body.to_string().as_bytes())
+    }
+
+    /// Changes the name or icon of a topic, `None` keeps them and an empty
+    /// `icon_custom_emoji_id` removes the icon.
+    pub fn edit_forum_topic(
+        &self,
+        chat_id: ChatId,
+        message_thread_id: i32,
+        name: Option<String>,
+        icon_custom_emoji_id: Option<String>,
+    ) -> Result<True> {
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_thread_id": message_thread_id,
+        });
+        if let Some(name) = name {
+            body["name"] = serde_json::json!(name);
+        }
+        if let Some(icon_custom_emoji_id) = icon_custom_emoji_id {
+            body["icon_custom_emoji_id"] = serde_json::json!(icon_custom_emoji_id);
+        }
+        self.request(Method::EditForumTopic, body.to_string().as_bytes())
+    }
+
+    pub fn close_forum_topic(&self, chat_id: ChatId, message_thread_id: i32) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_thread_id": message_thread_id,
+        });
+        self.request(Method::CloseForumTopic, body.to_string().as_bytes())
+    }
+
+    pub fn reopen_forum_topic(&self, chat_id: ChatId, message_thread_id: i32) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_thread_id": message_thread_id,
+        });
+        self.request(Method::ReopenForumTopic, body.to_string().as_bytes())
+    }
+
+    /// Deletes a topic along with all its messages.
+    pub fn delete_forum_topic(&self, chat_id: ChatId, message_thread_id: i32) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_thread_id": message_thread_id,
+        });
+        self.request(Method::DeleteForumTopic, body.to_string().as_bytes())
+    }
+
+    pub fn unpin_all_forum_topic_messages(&self, chat_id: ChatId, message_thread_id: i32) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_thread_id": message_thread_id,
+        });
+        self.request(Method::UnpinAllForumTopicMessages, body.to_string().as_bytes())
+    }
+
+    pub fn edit_general_forum_topic<N: Into<String>>(&self, chat_id: ChatId, name: N) -> Result<True> {
+        let name: String = name.into();
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "name": name,
+        });
+        self.request(Method::EditGeneralForumTopic, body.to_string().as_bytes())
+    }
+
+    pub fn close_general_forum_topic(&self, chat_id: ChatId) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+        });
+        self.request(Method::CloseGeneralForumTopic, body.to_string().as_bytes())
+    }
+
+    /// Reopens the General topic, unhiding it if it was hidden.
+    pub fn reopen_general_forum_topic(&self, chat_id: ChatId) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+        });
+        self.request(Method::ReopenGeneralForumTopic, body.to_string().as_bytes())
+    }
+
+    /// Hides the General topic, closing it if it was open.
+    pub fn hide_general_forum_topic(&self, chat_id: ChatId) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+        });
+        self.request(Method::HideGeneralForumTopic, body.to_string().as_bytes())
+    }
+
+    pub fn unhide_general_forum_topic(&self, chat_id: ChatId) -> Result<True> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+        });
+        self.request(Method::UnhideGeneralForumTopic, body.to_string().as_bytes())
+    }
+
+    /// Answers a callback query, which stops the progress bar on its button.
+    pub fn answer_callback_query<S: Into<String>>(&self, callback_query_id: S) -> Result<True> {
+        let callback_query_id: String = callback_query_id.into();
+        let body = serde_json::json!({
+            "callback_query_id": callback_query_id,
+        });
+        self.request(Method::AnswerCallbackQuery, body.to_string().as_bytes())
+    }
+
+    /// Answers a callback query with a notification at the top of the chat,
+    /// or an alert if `show_alert` is set.
+    pub fn answer_callback_query_with_text<S: Into<String>, T: Into<String>>(
+        &self,
+        callback_query_id: S,
         text: T,
-    ) -> Result<Message>
-    where
-        T: Into<String>,
-    {
-        let text = text.into();
+        show_alert: bool,
+    ) -> Result<True> {
+        let callback_query_id: String = callback_query_id.into();
+        let text: String = text.into();
         let body = serde_json::json!({
+            "callback_query_id": callback_query_id,
+            "text": text,
+            "show_alert": show_alert,
+        });
+        self.request(Method::AnswerCallbackQuery, body.to_string().as_bytes())
+    }
+
+    /// Answers a callback query by opening `url`, the url of a game for
+    /// [`CallbackQuery::game_short_name`] or a `t.me/<bot>?start=` link.
+    pub fn answer_callback_query_with_url<S: Into<String>>(
+        &self,
+        callback_query_id: S,
+        url: Url,
+    ) -> Result<True> {
+        let callback_query_id: String = callback_query_id.into();
+        let body = serde_json::json!({
+            "callback_query_id": callback_query_id,
+            "url": url,
+        });
+        self.request(Method::AnswerCallbackQuery, body.to_string().as_bytes())
+    }
+
+    /// Sends a message on behalf of the user of a Web App, with the
+    /// [`InitData::query_id`] it was opened with, and closes it.
+    ///
+    /// [`InitData::query_id`]: crate::InitData::query_id
+    pub fn answer_web_app_query<Q: Into<String>, R: Into<InlineQueryResult>>(
+        &self,
+        web_app_query_id: Q,
+        result: R,
+    ) -> Result<SentWebAppMessage> {
+        let web_app_query_id: String = web_app_query_id.into();
+        let body = serde_json::json!({
+            "web_app_query_id": web_app_query_id,
+            "result": result.into(),
+        });
+        self.request(Method::AnswerWebAppQuery, body.to_string().as_bytes())
+    }
+
+    /// Replaces the text of a message with plain text, or [`RichText`] with
+    /// its formatting.
+    pub fn edit_message_text<T: Into<RichText>>(
+        &self,
+        chat_id: ChatId,
+        message_id: MessageId,
+        text: T,
+    ) -> Result<Message> {
+        let text: RichText = text.into();
+        let mut body = serde_json::json!({
             "chat_id": chat_id,
             "message_id": message_id.0,
-            "text": text,
         });
+        flatten_into(&mut body, &text);
         self.request(Method::EditMessageText, body.to_string().as_bytes())
     }
 
-    pub fn edit_message_text_with_parse_mode<T>(
+    pub fn edit_message_text_with_parse_mode<T: Into<String>>(
         &self,
         chat_id: ChatId,
         message_id: MessageId,
         text: T,
         parse_mode: ParseMode,
-    ) -> Result<Message>
-    where
-        T: Into<String>,
-    {
+    ) -> Result<Message> {
         let text = text.into();
         let body = serde_json::json!({
             "chat_id": chat_id,
@@ -611,6 +1159,24 @@ impl Telegram {
         self.request(Method::EditMessageText, body.to_string().as_bytes())
     }
 
+    /// Replaces the inline keyboard of a message, or removes it if
+    /// `reply_markup` is `None`.
+    pub fn edit_message_reply_markup(
+        &self,
+        chat_id: ChatId,
+        message_id: MessageId,
+        reply_markup: Option<InlineKeyboardMarkup>,
+    ) -> Result<Message> {
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_id": message_id.0,
+        });
+        if let Some(reply_markup) = reply_markup {
+            body["reply_markup"] = serde_json::json!(reply_markup);
+        }
+        self.request(Method::EditMessageReplyMarkup, body.to_string().as_bytes())
+    }
+
     pub fn delete_message(&self, chat_id: ChatId, message_id: MessageId) -> Result<True> {
         let body = serde_json::json!({
             "chat_id": chat_id,
@@ -626,4 +1192,772 @@ impl Telegram {
         });
         self.request(Method::DeleteMessages, body.to_string().as_bytes())
     }
+
+    pub fn get_sticker_set<N: Into<String>>(&self, name: N) -> Result<StickerSet> {
+        let name: String = name.into();
+        let body = serde_json::json!({
+            "name": name,
+        });
+        self.request(Method::GetStickerSet, body.to_string().as_bytes())
+    }
+
+    /// Returns the stickers of custom emojis, see
+    /// [`MessageEntityKind::CustomEmoji`].
+    ///
+    /// [`MessageEntityKind::CustomEmoji`]: crate::MessageEntityKind::CustomEmoji
+    pub fn get_custom_emoji_stickers(&self, custom_emoji_ids: Vec<String>) -> Result<Vec<Sticker>> {
+        let body = serde_json::json!({
+            "custom_emoji_ids": custom_emoji_ids,
+        });
+        self.request(Method::GetCustomEmojiStickers, body.to_string().as_bytes())
+    }
+
+    /// Uploads a `.png` sticker to use in [`create_new_sticker_set`] or
+    /// [`add_sticker_to_set`] later, possibly several times.
+    ///
+    /// [`create_new_sticker_set`]: Self::create_new_sticker_set
+    /// [`add_sticker_to_set`]: Self::add_sticker_to_set
+    pub fn upload_sticker_file(&self, user_id: UserId, png_sticker: InputFile) -> Result<File> {
+        let body = serde_json::json!({
+            "user_id": user_id,
+            "png_sticker": png_sticker,
+        });
+        self.request_multipart(Method::UploadStickerFile, body, vec![png_sticker])
+    }
+
+    /// Creates a sticker set owned by `user_id`, with `sticker` in it.
+    ///
+    /// `name` must end with `_by_<bot username>`, `emojis` are the ones
+    /// `sticker` corresponds to and `mask_position` is where masks are placed
+    /// on faces.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_new_sticker_set<N: Into<String>, T: Into<String>, E: Into<String>>(
+        &self,
+        user_id: UserId,
+        name: N,
+        title: T,
+        sticker: InputSticker,
+        emojis: E,
+        sticker_type: StickerType,
+        mask_position: Option<MaskPosition>,
+    ) -> Result<True> {
+        let name: String = name.into();
+        let title: String = title.into();
+        let emojis: String = emojis.into();
+        let mut body = serde_json::json!({
+            "user_id": user_id,
+            "name": name,
+            "title": title,
+            "emojis": emojis,
+            "mask_position": mask_position,
+        });
+        flatten_into(&mut body, &sticker_type);
+        flatten_into(&mut body, &sticker);
+        let files = input_files(sticker);
+        self.request_multipart(Method::CreateNewStickerSet, body, files)
+    }
+
+    /// Adds `sticker` to a set created by the bot, see
+    /// [`create_new_sticker_set`](Self::create_new_sticker_set).
+    pub fn add_sticker_to_set<N: Into<String>, E: Into<String>>(
+        &self,
+        user_id: UserId,
+        name: N,
+        sticker: InputSticker,
+        emojis: E,
+        mask_position: Option<MaskPosition>,
+    ) -> Result<True> {
+        let name: String = name.into();
+        let emojis: String = emojis.into();
+        let mut body = serde_json::json!({
+            "user_id": user_id,
+            "name": name,
+            "emojis": emojis,
+            "mask_position": mask_position,
+        });
+        flatten_into(&mut body, &sticker);
+        let files = input_files(sticker);
+        self.request_multipart(Method::AddStickerToSet, body, files)
+    }
+
+    /// Moves a sticker, by file id, to the zero-based `position` in its set.
+    pub fn set_sticker_position_in_set<S: Into<String>>(
+        &self,
+        sticker: S,
+        position: u32,
+    ) -> Result<True> {
+        let sticker: String = sticker.into();
+        let body = serde_json::json!({
+            "sticker": sticker,
+            "position": position,
+        });
+        self.request(Method::SetStickerPositionInSet, body.to_string().as_bytes())
+    }
+
+    /// Deletes a sticker, by file id, from a set created by the bot.
+    pub fn delete_sticker_from_set<S: Into<String>>(&self, sticker: S) -> Result<True> {
+        let sticker: String = sticker.into();
+        let body = serde_json::json!({
+            "sticker": sticker,
+        });
+        self.request(Method::DeleteStickerFromSet, body.to_string().as_bytes())
+    }
+
+    /// Sets the thumbnail of a set, in the format of its stickers, or removes
+    /// it so the first sticker is used instead.
+    pub fn set_sticker_set_thumb<N: Into<String>>(
+        &self,
+        name: N,
+        user_id: UserId,
+        thumb: Option<InputFile>,
+    ) -> Result<True> {
+        let name: String = name.into();
+        let body = serde_json::json!({
+            "name": name,
+            "user_id": user_id,
+            "thumb": thumb,
+        });
+        let files = input_files(thumb);
+        self.request_multipart(Method::SetStickerSetThumb, body, files)
+    }
+
+    /// Returns a link for paying `invoice`.
+    pub fn create_invoice_link(&self, invoice: NewInvoice) -> Result<String> {
+        let body = serde_json::to_value(invoice).expect("invoice is serializable");
+        self.request(Method::CreateInvoiceLink, body.to_string().as_bytes())
+    }
+
+    /// Accepts a [`ShippingQuery`] with the available shipping options.
+    ///
+    /// [`ShippingQuery`]: crate::ShippingQuery
+    pub fn answer_shipping_query<T: Into<String>>(
+        &self,
+        shipping_query_id: T,
+        shipping_options: Vec<ShippingOption>,
+    ) -> Result<True> {
+        let shipping_query_id: String = shipping_query_id.into();
+        let body = serde_json::json!({
+            "shipping_query_id": shipping_query_id,
+            "ok": true,
+            "shipping_options": shipping_options,
+        });
+        self.request(Method::AnswerShippingQuery, body.to_string().as_bytes())
+    }
+
+    /// Rejects a [`ShippingQuery`], e.g. because delivery to the address is
+    /// impossible, `error_message` is shown to the user.
+    ///
+    /// [`ShippingQuery`]: crate::ShippingQuery
+    pub fn answer_shipping_query_with_error<T: Into<String>, E: Into<String>>(
+        &self,
+        shipping_query_id: T,
+        error_message: E,
+    ) -> Result<True> {
+        let shipping_query_id: String = shipping_query_id.into();
+        let error_message: String = error_message.into();
+        let body = serde_json::json!({
+            "shipping_query_id": shipping_query_id,
+            "ok": false,
+            "error_message": error_message,
+        });
+        self.request(Method::AnswerShippingQuery, body.to_string().as_bytes())
+    }
+
+    /// Confirms a [`PreCheckoutQuery`], it must be answered within 10 seconds.
+    ///
+    /// [`PreCheckoutQuery`]: crate::PreCheckoutQuery
+    pub fn answer_pre_checkout_query<T: Into<String>>(
+        &self,
+        pre_checkout_query_id: T,
+    ) -> Result<True> {
+        let pre_checkout_query_id: String = pre_checkout_query_id.into();
+        let body = serde_json::json!({
+            "pre_checkout_query_id": pre_checkout_query_id,
+            "ok": true,
+        });
+        self.request(Method::AnswerPreCheckoutQuery, body.to_string().as_bytes())
+    }
+
+    /// Rejects a [`PreCheckoutQuery`], e.g. because the goods sold out,
+    /// `error_message` is shown to the user.
+    ///
+    /// [`PreCheckoutQuery`]: crate::PreCheckoutQuery
+    pub fn answer_pre_checkout_query_with_error<T: Into<String>, E: Into<String>>(
+        &self,
+        pre_checkout_query_id: T,
+        error_message: E,
+    ) -> Result<True> {
+        let pre_checkout_query_id: String = pre_checkout_query_id.into();
+        let error_message: String = error_message.into();
+        let body = serde_json::json!({
+            "pre_checkout_query_id": pre_checkout_query_id,
+            "ok": false,
+            "error_message": error_message,
+        });
+        self.request(Method::AnswerPreCheckoutQuery, body.to_string().as_bytes())
+    }
+
+    pub fn set_passport_data_errors(
+        &self,
+        user_id: UserId,
+        errors: Vec<PassportElementError>,
+    ) -> Result<True> {
+        let body = serde_json::json!({
+            "user_id": user_id,
+            "errors": errors,
+        });
+        self.request(Method::SetPassportDataErrors, body.to_string().as_bytes())
+    }
+
+    /// Sets the score of a user in the game sent in a message, and returns
+    /// the edited message.
+    ///
+    /// Unless `force` is set, a score lower than the current one is an error.
+    /// The message shows the new score table unless `disable_edit_message` is
+    /// set.
+    pub fn set_game_score(
+        &self,
+        user_id: UserId,
+        score: u32,
+        chat_id: ChatId,
+        message_id: MessageId,
+        force: bool,
+        disable_edit_message: bool,
+    ) -> Result<Message> {
+        let body = serde_json::json!({
+            "user_id": user_id,
+            "score": score,
+            "chat_id": chat_id,
+            "message_id": message_id.0,
+            "force": force,
+            "disable_edit_message": disable_edit_message,
+        });
+        self.request(Method::SetGameScore, body.to_string().as_bytes())
+    }
+
+    /// Same as [`set_game_score`](Self::set_game_score), for a game sent via
+    /// inline mode.
+    pub fn set_game_score_inline<S: Into<String>>(
+        &self,
+        user_id: UserId,
+        score: u32,
+        inline_message_id: S,
+        force: bool,
+        disable_edit_message: bool,
+    ) -> Result<True> {
+        let inline_message_id: String = inline_message_id.into();
+        let body = serde_json::json!({
+            "user_id": user_id,
+            "score": score,
+            "inline_message_id": inline_message_id,
+            "force": force,
+            "disable_edit_message": disable_edit_message,
+        });
+        self.request(Method::SetGameScore, body.to_string().as_bytes())
+    }
+
+    /// Returns the scores of `user_id` and several of their neighbors in the
+    /// game sent in a message.
+    pub fn get_game_high_scores(
+        &self,
+        user_id: UserId,
+        chat_id: ChatId,
+        message_id: MessageId,
+    ) -> Result<Vec<GameHighScore>> {
+        let body = serde_json::json!({
+            "user_id": user_id,
+            "chat_id": chat_id,
+            "message_id": message_id.0,
+        });
+        self.request(Method::GetGameHighScores, body.to_string().as_bytes())
+    }
+
+    /// Same as [`get_game_high_scores`](Self::get_game_high_scores), for a
+    /// game sent via inline mode.
+    pub fn get_game_high_scores_inline<S: Into<String>>(
+        &self,
+        user_id: UserId,
+        inline_message_id: S,
+    ) -> Result<Vec<GameHighScore>> {
+        let inline_message_id: String = inline_message_id.into();
+        let body = serde_json::json!({
+            "user_id": user_id,
+            "inline_message_id": inline_message_id,
+        });
+        self.request(Method::GetGameHighScores, body.to_string().as_bytes())
+    }
+}
+
+impl Telegram {
+    /// Answers `query` with the url of its game if it comes from the Play
+    /// button of one, `game_url` returns the url of a game by its short name.
+    ///
+    /// Returns `Ok(false)` without answering other queries, or games
+    /// `game_url` doesn't know.
+    pub fn answer_game_query<F>(&self, query: &CallbackQuery, game_url: F) -> Result<bool>
+    where
+        F: FnOnce(&str) -> Option<Url>,
+    {
+        match query.game_short_name.as_deref().and_then(game_url) {
+            Some(url) => self
+                .answer_callback_query_with_url(query.id.clone(), url)
+                .map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Downloads the contents of `file`, from [`get_file`].
+    ///
+    /// Files are downloaded from the base url with `file/` before its last
+    /// segment, e.g. `https://api.telegram.org/file/bot<token>/<path>`, and
+    /// don't go through middlewares.
+    ///
+    /// [`get_file`]: Telegram::get_file
+    pub fn download_file(&self, file: &File) -> Result<Bytes> {
+        let mut contents = Vec::new();
+        self.download_file_to(file, &mut contents)?;
+        Ok(contents.into())
+    }
+
+    /// Downloads the contents of `file` into `writer`, see [`download_file`].
+    ///
+    /// The file is written as it arrives if the transport supports it, see
+    /// [`Transport::send_to`], so `writer` may have part of it on errors.
+    ///
+    /// [`download_file`]: Telegram::download_file
+    pub fn download_file_to<W>(&self, file: &File, writer: &mut W) -> Result<()>
+    where
+        W: Write,
+    {
+        let url = file_url(self.base_url, &self.token, file);
+        let mut writer = CountingWriter {
+            inner: writer,
+            written: 0,
+        };
+        let response = self
+            .transport
+            .send_to(HttpRequest::get(url), &mut writer)
+            .with_context(|| format!("downloading {} failed", file.path))?;
+
+        check_download(file, &response, writer.written)
+    }
+
+    /// Downloads the file with `file_id`, e.g. of a [`PhotoSize`] or
+    /// [`Document`], with [`get_file`] and [`download_file`].
+    ///
+    /// [`PhotoSize`]: crate::PhotoSize
+    /// [`Document`]: crate::Document
+    /// [`get_file`]: Telegram::get_file
+    /// [`download_file`]: Telegram::download_file
+    pub fn download_by_id<S>(&self, file_id: S) -> Result<Bytes>
+    where
+        S: Into<String>,
+    {
+        self.download_file(&self.get_file(file_id.into())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        sync::Arc,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    use serde_json::{json, Value};
+
+    use crate::{
+        ApiError, CallbackQuery, ChatAdministratorRights, ChatId, ChatPermissions, Currency,
+        HttpRequest, HttpResponse, InlineQueryResultArticle, InputFile, InputMessageContent,
+        InputMessageContentText, InputSticker, LabeledPrice, MessageId, NewInvoice,
+        ReplayTransport, RichText, ShippingOption, StickerType, Telegram, Transport, UntilDate,
+        UserId,
+    };
+
+    #[test]
+    fn payments() {
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push_result(json!("https://t.me/$invoice"));
+        for _ in 0..3 {
+            replay.push_result(json!(true));
+        }
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        let invoice = NewInvoice::new(
+            "Tea",
+            "A pot of tea",
+            "order-1",
+            Currency::JPY,
+            [LabeledPrice::new("Tea", 500)],
+        )
+        .is_flexible(true);
+        assert_eq!(
+            tele.create_invoice_link(invoice.clone()).unwrap(),
+            "https://t.me/$invoice"
+        );
+        let delivery = ShippingOption::new("post", "Post", [LabeledPrice::new("Post", 300)]);
+        assert!(tele.answer_shipping_query("sq", vec![delivery]).is_ok());
+        assert!(tele
+            .answer_pre_checkout_query_with_error("pcq", "Sold out")
+            .is_ok());
+
+        // the replayed `true` is not a `Message`
+        assert!(tele.send_invoice(ChatId(7), invoice).is_err());
+
+        let bodies = replay.calls();
+        assert_eq!(bodies[0].0, "createInvoiceLink");
+        assert_eq!(bodies[0].1["currency"], "JPY");
+        assert_eq!(bodies[0].1["prices"][0]["amount"], 500);
+        assert_eq!(bodies[1].0, "answerShippingQuery");
+        assert_eq!(
+            bodies[1].1,
+            json!({
+                "shipping_query_id": "sq",
+                "ok": true,
+                "shipping_options": [
+                    { "id": "post", "title": "Post", "prices": [{ "label": "Post", "amount": 300 }] },
+                ],
+            })
+        );
+        assert_eq!(bodies[2].0, "answerPreCheckoutQuery");
+        assert_eq!(bodies[2].1["ok"], false);
+        assert_eq!(bodies[2].1["error_message"], "Sold out");
+        assert_eq!(bodies[3].0, "sendInvoice");
+        assert_eq!(bodies[3].1["chat_id"], 7);
+        assert_eq!(bodies[3].1["is_flexible"], true);
+    }
+
+    #[test]
+    fn stickers() {
+        let replay = Arc::new(ReplayTransport::default());
+        for _ in 0..3 {
+            replay.push_result(json!(true));
+        }
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        let upload = InputFile::memory(&b"PNG"[..]).file_name("cat.png");
+        assert!(tele
+            .create_new_sticker_set(
+                UserId(7),
+                "cats_by_bot",
+                "Cats",
+                InputSticker::Png(upload),
+                "🐱",
+                StickerType::Regular,
+                None,
+            )
+            .is_ok());
+        let uploaded = InputFile::file_id("AgADWAIAAnW3iEU");
+        assert!(tele
+            .add_sticker_to_set(
+                UserId(7),
+                "cats_by_bot",
+                InputSticker::Png(uploaded),
+                "😺",
+                None
+            )
+            .is_ok());
+        assert!(tele
+            .set_chat_sticker_set(ChatId(-100), "cats_by_bot")
+            .is_ok());
+
+        let requests = replay.requests();
+        let form = String::from_utf8(requests[0].body.clone()).unwrap();
+        assert!(requests[0].url.ends_with("/createNewStickerSet"));
+        assert!(requests[0].headers[0]
+            .1
+            .starts_with("multipart/form-data; boundary="));
+        assert!(form.contains("name=\"sticker_type\"\r\n\r\nregular\r\n"));
+        assert!(form.contains("name=\"png_sticker\"\r\n\r\nattach://"));
+        assert!(form.contains(
+            "filename=\"cat.png\"\r\nContent-Type: application/octet-stream\r\n\r\nPNG\r\n"
+        ));
+        assert!(!form.contains("mask_position"));
+
+        let body: Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "user_id": 7,
+                "name": "cats_by_bot",
+                "png_sticker": "AgADWAIAAnW3iEU",
+                "emojis": "😺",
+            })
+        );
+        assert!(requests[2].url.ends_with("/setChatStickerSet"));
+    }
+
+    #[test]
+    fn games() {
+        let replay = Arc::new(ReplayTransport::default());
+        for _ in 0..2 {
+            replay.push_result(json!(true));
+        }
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        let query = |game: Option<&str>| -> CallbackQuery {
+            serde_json::from_value(json!({
+                "id": "cq",
+                "from": { "id": 7, "is_bot": false, "first_name": "Ann" },
+                "chat_instance": "ci",
+                "inline_message_id": "im",
+                "game_short_name": game,
+            }))
+            .unwrap()
+        };
+        let game_url =
+            |name: &str| (name == "snake").then(|| "https://example.com/snake".parse().unwrap());
+
+        assert!(tele
+            .answer_game_query(&query(Some("snake")), game_url)
+            .unwrap());
+        assert!(!tele
+            .answer_game_query(&query(Some("chess")), game_url)
+            .unwrap());
+        assert!(!tele.answer_game_query(&query(None), game_url).unwrap());
+        assert!(tele
+            .set_game_score_inline(UserId(7), 120, "im", true, false)
+            .is_ok());
+
+        let bodies = replay.calls();
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(bodies[0].0, "answerCallbackQuery");
+        assert_eq!(
+            bodies[0].1,
+            json!({ "callback_query_id": "cq", "url": "https://example.com/snake" })
+        );
+        assert_eq!(bodies[1].0, "setGameScore");
+        assert_eq!(
+            bodies[1].1,
+            json!({
+                "user_id": 7,
+                "score": 120,
+                "inline_message_id": "im",
+                "force": true,
+                "disable_edit_message": false,
+            })
+        );
+    }
+
+    #[test]
+    fn web_app_query() {
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push_result(json!({ "inline_message_id": "im" }));
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        let content = InputMessageContent::Text(InputMessageContentText::new("Ordered!"));
+        let result = InlineQueryResultArticle::new("order", "Order", content);
+        let sent = tele.answer_web_app_query("wq", result).unwrap();
+        assert_eq!(sent.inline_message_id.as_deref(), Some("im"));
+
+        let bodies = replay.calls();
+        assert_eq!(bodies[0].0, "answerWebAppQuery");
+        assert_eq!(
+            bodies[0].1,
+            json!({
+                "web_app_query_id": "wq",
+                "result": {
+                    "type": "article",
+                    "id": "order",
+                    "title": "Order",
+                    "input_message_content": { "message_text": "Ordered!" },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn administration() {
+        let replay = Arc::new(ReplayTransport::default());
+        for _ in 0..3 {
+            replay.push_result(json!(true));
+        }
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        let rights = ChatAdministratorRights {
+            can_delete_messages: true,
+            can_pin_messages: Some(true),
+            ..Default::default()
+        };
+        assert!(tele
+            .promote_chat_member(ChatId(-100), UserId(7), rights)
+            .is_ok());
+
+        let until_date = UntilDate::after(Duration::from_secs(600));
+        assert!(tele
+            .restrict_chat_member_until(
+                ChatId(-100),
+                UserId(7),
+                ChatPermissions::empty(),
+                until_date,
+                true,
+            )
+            .is_ok());
+        assert!(tele
+            .ban_chat_sender_chat(ChatId(-100), ChatId(-1001234567890))
+            .is_ok());
+
+        let bodies = replay.calls();
+        assert_eq!(bodies[0].1["can_delete_messages"], true);
+        assert_eq!(bodies[0].1["can_pin_messages"], true);
+        assert_eq!(bodies[0].1["can_promote_members"], false);
+        assert!(bodies[0].1.get("can_post_messages").is_none());
+
+        let UntilDate::Date(date) = until_date else {
+            panic!("ten minutes is not forever");
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let now = now.as_secs() as i64;
+        assert!((now + 598..=now + 600).contains(&date.timestamp()));
+        assert_eq!(bodies[1].1["until_date"], date.timestamp());
+        assert!(bodies[1].1["permissions"]
+            .get("can_send_messages")
+            .is_none());
+        assert_eq!(bodies[1].1["use_independent_chat_permissions"], true);
+
+        assert_eq!(bodies[2].1["sender_chat_id"], -1001234567890_i64);
+    }
+
+    #[test]
+    fn rich_text() {
+        let replay = Arc::new(ReplayTransport::default());
+        let message = json!({
+            "message_id": 5,
+            "date": 0,
+            "chat": { "id": 42, "type": "private", "first_name": "Ann" },
+            "text": "hi there",
+        });
+        replay.push_result(message.clone());
+        replay.push_result(message);
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        let text = RichText::new().text("hi ").bold("*there*");
+        tele.send_message(ChatId(42), text).unwrap();
+        tele.edit_message_text(ChatId(42), MessageId(5), "plain")
+            .unwrap();
+
+        let bodies = replay.calls();
+        assert_eq!(
+            bodies[0].1,
+            json!({
+                "chat_id": 42,
+                "text": "hi *there*",
+                "entities": [{ "type": "bold", "offset": 3, "length": 7 }],
+            })
+        );
+        assert_eq!(
+            bodies[1].1,
+            json!({ "chat_id": 42, "message_id": 5, "text": "plain" })
+        );
+    }
+
+    #[test]
+    fn long_message() {
+        let replay = Arc::new(ReplayTransport::default());
+        for id in [10, 11] {
+            replay.push_result(json!({
+                "message_id": id,
+                "date": 0,
+                "chat": { "id": 42, "type": "private", "first_name": "Ann" },
+                "text": "…",
+            }));
+        }
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        let paragraph = "word ".repeat(500);
+        let text = RichText::new().bold(format!("{paragraph}\n\n{paragraph}"));
+        let sent = tele.send_long_message(ChatId(42), text).unwrap();
+        assert_eq!(sent.len(), 2);
+
+        replay.push_result(json!({
+            "message_id": 12,
+            "date": 0,
+            "chat": { "id": 42, "type": "private", "first_name": "Ann" },
+            "text": "…",
+        }));
+        let sent = tele
+            .in_thread(4)
+            .send_long_message(ChatId(42), "hi")
+            .unwrap();
+        assert_eq!(sent.len(), 1);
+
+        let bodies = replay.calls();
+        assert_eq!(bodies[0].1.get("reply_to_message_id"), None);
+        assert_eq!(bodies[1].1["reply_to_message_id"], 10);
+        for (_, body) in &bodies[..2] {
+            assert_eq!(body["text"], paragraph.trim_end());
+            assert_eq!(body["entities"][0]["length"], 2499);
+        }
+        assert_eq!(bodies[2].1.get("reply_to_message_id"), None);
+        assert_eq!(bodies[2].1["message_thread_id"], 4);
+    }
+
+    #[test]
+    fn download_file() {
+        let replay = Arc::new(ReplayTransport::default());
+        let file = json!({
+            "file_id": "f1",
+            "file_unique_id": "u1",
+            "file_size": 5,
+            "file_path": "photos/file_1.jpg",
+        });
+        replay.push_result(file.clone());
+        replay.push(HttpResponse::new(200, "hello"));
+        replay.push(HttpResponse::new(200, "hell"));
+        replay.push_error(404, "Not Found");
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        assert_eq!(tele.download_by_id("f1").unwrap(), "hello");
+
+        let file = serde_json::from_value(file).unwrap();
+        let mut written = Vec::new();
+        let err = tele.download_file_to(&file, &mut written).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "downloaded 4 bytes of photos/file_1.jpg, expected 5"
+        );
+        assert_eq!(written, b"hell");
+        let err = tele.download_file(&file).unwrap_err();
+        assert_eq!(err.downcast::<ApiError>().unwrap().error_code, 404);
+
+        let requests = replay.requests();
+        assert_eq!(requests[0].url, "https://api.telegram.org/botTOKEN/getFile");
+        assert_eq!(requests[1].method, "GET");
+        assert_eq!(
+            requests[1].url,
+            "https://api.telegram.org/file/botTOKEN/photos/file_1.jpg"
+        );
+    }
+
+    #[test]
+    fn download_file_to_streams() {
+        struct Streaming;
+
+        impl Transport for Streaming {
+            fn send(&self, _: HttpRequest) -> anyhow::Result<HttpResponse> {
+                unreachable!("downloads go through send_to")
+            }
+
+            fn send_to(
+                &self,
+                request: HttpRequest,
+                writer: &mut dyn Write,
+            ) -> anyhow::Result<HttpResponse> {
+                writer.write_all(request.url.as_bytes())?;
+                Ok(HttpResponse::new(200, Vec::new()))
+            }
+        }
+
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(Streaming);
+        let file = serde_json::from_value(json!({
+            "file_id": "f1",
+            "file_unique_id": "u1",
+            "file_path": "a.txt",
+        }))
+        .unwrap();
+        let mut written = Vec::new();
+        tele.download_file_to(&file, &mut written).unwrap();
+        assert_eq!(written, b"https://api.telegram.org/file/botTOKEN/a.txt");
+    }
 }
@@ -0,0 +1,764 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ApiError, CallbackQuery, ChatId, ChatPermissions, InlineKeyboardButton, Message, MessageEntity,
+    MessageEntityKind, MessageId, ReplyMarkup, Telegram, UntilDate, User, UserId,
+};
+
+const CAPTCHA_PREFIX: &str = "captcha:";
+
+/// What a [`Moderator`] does to a member breaking a rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Only reports the violation.
+    Flag,
+
+    /// Deletes the message.
+    Delete,
+
+    /// Deletes the message and keeps the member from writing for a while.
+    Mute(Duration),
+
+    /// Deletes the message and removes the member, who may join again.
+    Kick,
+
+    /// Deletes the message and bans the member.
+    Ban,
+}
+
+/// A rule a member broke.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Violation {
+    /// The first message of a new member contains a link.
+    LinkInFirstMessage,
+
+    /// The member sent too many messages in a short time.
+    Flood,
+
+    /// The message is forwarded from a channel.
+    ChannelForward,
+
+    /// The member didn't solve the captcha in time.
+    CaptchaExpired,
+}
+
+/// What a [`Moderator`] did.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModerationEvent {
+    Violation {
+        chat_id: ChatId,
+        user_id: UserId,
+        violation: Violation,
+        action: Action,
+    },
+
+    /// A new member was muted until they press the captcha button.
+    CaptchaSent { chat_id: ChatId, user_id: UserId },
+
+    /// The member pressed the captcha button and can write.
+    CaptchaSolved { chat_id: ChatId, user_id: UserId },
+}
+
+/// A captcha waiting for a new member to press its button.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Captcha {
+    /// The message with the button.
+    pub message_id: MessageId,
+
+    /// Unix time after which the captcha expires.
+    pub expires_at: i64,
+}
+
+/// What a [`Moderator`] remembers about a member of a chat.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberState {
+    /// Whether the member was seen joining the chat.
+    #[serde(default)]
+    pub joined: bool,
+
+    /// Number of messages the member has sent.
+    pub messages: u32,
+
+    /// Unix times of the messages the member sent recently.
+    pub recent: Vec<i64>,
+
+    pub captcha: Option<Captcha>,
+}
+
+/// Where a [`Moderator`] keeps the state of members.
+pub trait ModerationStore {
+    fn load(&self, chat_id: ChatId, user_id: UserId) -> Result<Option<MemberState>>;
+
+    fn save(&self, chat_id: ChatId, user_id: UserId, state: &MemberState) -> Result<()>;
+
+    /// Returns the captchas no member has solved yet.
+    fn pending_captchas(&self) -> Result<Vec<(ChatId, UserId, Captcha)>>;
+}
+
+impl<S> ModerationStore for &S
+where
+    S: ModerationStore + ?Sized,
+{
+    fn load(&self, chat_id: ChatId, user_id: UserId) -> Result<Option<MemberState>> {
+        (**self).load(chat_id, user_id)
+    }
+
+    fn save(&self, chat_id: ChatId, user_id: UserId, state: &MemberState) -> Result<()> {
+        (**self).save(chat_id, user_id, state)
+    }
+
+    fn pending_captchas(&self) -> Result<Vec<(ChatId, UserId, Captcha)>> {
+        (**self).pending_captchas()
+    }
+}
+
+/// A [`ModerationStore`] that lives as long as the process does.
+#[derive(Debug, Default)]
+pub struct MemoryModerationStore {
+    members: Mutex<HashMap<(ChatId, UserId), MemberState>>,
+}
+
+impl MemoryModerationStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ModerationStore for MemoryModerationStore {
+    fn load(&self, chat_id: ChatId, user_id: UserId) -> Result<Option<MemberState>> {
+        let members = self.members.lock().unwrap();
+        Ok(members.get(&(chat_id, user_id)).cloned())
+    }
+
+    fn save(&self, chat_id: ChatId, user_id: UserId, state: &MemberState) -> Result<()> {
+        let mut members = self.members.lock().unwrap();
+        members.insert((chat_id, user_id), state.clone());
+        Ok(())
+    }
+
+    fn pending_captchas(&self) -> Result<Vec<(ChatId, UserId, Captcha)>> {
+        let members = self.members.lock().unwrap();
+        let pending = members.iter().filter_map(|(&(chat_id, user_id), state)| {
+            let captcha = state.captcha.clone()?;
+            Some((chat_id, user_id, captcha))
+        });
+        Ok(pending.collect())
+    }
+}
+
+#[derive(Debug)]
+struct Flood {
+    max_messages: usize,
+    period: Duration,
+    action: Action,
+}
+
+#[derive(Debug)]
+struct CaptchaRule {
+    timeout: Duration,
+    action: Action,
+    text: String,
+    button: String,
+}
+
+/// Enforces anti-spam rules in groups where the bot is an admin allowed to
+/// delete messages and restrict members.
+///
+/// Every rule is off until enabled with its builder method, and then applies
+/// to everyone, so leave messages of admins out.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use tg_flows::{Action, MemoryModerationStore, Message, Moderator, Telegram};
+///
+/// # fn run(tele: Telegram, message: Message) -> anyhow::Result<()> {
+/// let moderator = Moderator::new(MemoryModerationStore::new())
+///     .first_message_links(Action::Ban)
+///     .flood(5, Duration::from_secs(10), Action::Mute(Duration::from_secs(600)))
+///     .channel_forwards(Action::Delete)
+///     .captcha(Duration::from_secs(120), Action::Kick);
+///
+/// for event in moderator.handle(&tele, &message)? {
+///     println!("{event:?}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Moderator<S> {
+    store: S,
+    links: Option<Action>,
+    flood: Option<Flood>,
+    channel_forwards: Option<Action>,
+    captcha: Option<CaptchaRule>,
+}
+
+impl<S> Moderator<S>
+where
+    S: ModerationStore,
+{
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            links: None,
+            flood: None,
+            channel_forwards: None,
+            captcha: None,
+        }
+    }
+
+    /// Takes `action` when the first message of a new member has a link.
+    ///
+    /// Only members seen joining are new, members who were in the chat
+    /// before the moderator, or before its store was emptied, aren't checked.
+    #[must_use]
+    pub fn first_message_links(mut self, action: Action) -> Self {
+        self.links = Some(action);
+        self
+    }
+
+    /// Takes `action` on messages beyond `max_messages` sent by a member
+    /// within `period`.
+    #[must_use]
+    pub fn flood(mut self, max_messages: usize, period: Duration, action: Action) -> Self {
+        self.flood = Some(Flood {
+            max_messages,
+            period,
+            action,
+        });
+        self
+    }
+
+    /// Takes `action` on messages forwarded from channels, except posts of
+    /// the linked channel Telegram forwards to its discussion group.
+    #[must_use]
+    pub fn channel_forwards(mut self, action: Action) -> Self {
+        self.channel_forwards = Some(action);
+        self
+    }
+
+    /// Mutes new members until they press a button, and takes `action` if
+    /// they don't within `timeout`, see [`Moderator::expire_captchas`].
+    #[must_use]
+    pub fn captcha(mut self, timeout: Duration, action: Action) -> Self {
+        self.captcha = Some(CaptchaRule {
+            timeout,
+            action,
+            text: String::from("Welcome, {name}! Press the button below to start writing."),
+            button: String::from("I'm not a robot"),
+        });
+        self
+    }
+
+    /// Changes the captcha message, where `{name}` is replaced with the name
+    /// of the new member, and its button.
+    #[must_use]
+    pub fn captcha_message<T, B>(mut self, text: T, button: B) -> Self
+    where
+        T: Into<String>,
+        B: Into<String>,
+    {
+        if let Some(captcha) = &mut self.captcha {
+            captcha.text = text.into();
+            captcha.button = button.into();
+        }
+        self
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Checks `message` against the rules and takes the action of the rule it
+    /// breaks, or records the members it announces as new and sends them
+    /// captchas.
+    pub fn handle(&self, tele: &Telegram, message: &Message) -> Result<Vec<ModerationEvent>> {
+        let chat_id = message.chat.id;
+
+        if let Some(members) = message.new_chat_members() {
+            let mut events = Vec::new();
+            for member in members.iter().filter(|member| !member.is_bot) {
+                let mut state = self.store.load(chat_id, member.id)?.unwrap_or_default();
+                state.joined = true;
+                if let Some(captcha) = &self.captcha {
+                    state.captcha = Some(send_captcha(tele, message, member, captcha)?);
+                    events.push(ModerationEvent::CaptchaSent {
+                        chat_id,
+                        user_id: member.id,
+                    });
+                }
+                self.store.save(chat_id, member.id, &state)?;
+            }
+            return Ok(events);
+        }
+
+        let Some(user) = message.from() else {
+            return Ok(Vec::new());
+        };
+        let mut state = self.store.load(chat_id, user.id)?.unwrap_or_default();
+        let violation = self.check(&mut state, message);
+        state.messages += 1;
+        self.store.save(chat_id, user.id, &state)?;
+
+        let Some((violation, action)) = violation else {
+            return Ok(Vec::new());
+        };
+        apply(tele, chat_id, user.id, Some(message.id), action)?;
+
+        Ok(vec![ModerationEvent::Violation {
+            chat_id,
+            user_id: user.id,
+            violation,
+            action,
+        }])
+    }
+
+    /// Lets a new member write if `query` is them pressing their captcha
+    /// button, returns `None` for other queries.
+    pub fn handle_callback(
+        &self,
+        tele: &Telegram,
+        query: &CallbackQuery,
+    ) -> Result<Option<ModerationEvent>> {
+        let data = query.data.as_deref().unwrap_or_default();
+        let (Some(user_id), Some(message)) = (
+            data.strip_prefix(CAPTCHA_PREFIX)
+                .and_then(|id| id.parse().ok())
+                .map(UserId),
+            &query.message,
+        ) else {
+            return Ok(None);
+        };
+        let chat_id = message.chat.id;
+
+        if query.from.id != user_id {
+            tele.answer_callback_query_with_text(query.id.as_str(), "This is not for you", false)?;
+            return Ok(None);
+        }
+
+        let mut state = self.store.load(chat_id, user_id)?.unwrap_or_default();
+        let Some(captcha) = state.captcha.take() else {
+            tele.answer_callback_query(query.id.as_str())?;
+            return Ok(None);
+        };
+
+        tele.restrict_chat_member(chat_id, user_id, ChatPermissions::all())?;
+        self.store.save(chat_id, user_id, &state)?;
+        tele.answer_callback_query(query.id.as_str())?;
+        ignore_api_error(tele.delete_message(chat_id, captcha.message_id))?;
+
+        Ok(Some(ModerationEvent::CaptchaSolved { chat_id, user_id }))
+    }
+
+    /// Takes the captcha action on the members who didn't solve their
+    /// captcha by `now`, call it regularly, e.g. on a schedule.
+    pub fn expire_captchas(
+        &self,
+        tele: &Telegram,
+        now: SystemTime,
+    ) -> Result<Vec<ModerationEvent>> {
+        let Some(rule) = &self.captcha else {
+            return Ok(Vec::new());
+        };
+        let now = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let now = i64::try_from(now.as_secs()).unwrap_or(i64::MAX);
+
+        let mut events = Vec::new();
+        for (chat_id, user_id, captcha) in self.store.pending_captchas()? {
+            if captcha.expires_at > now {
+                continue;
+            }
+
+            ignore_api_error(tele.delete_message(chat_id, captcha.message_id))?;
+            apply(tele, chat_id, user_id, None, rule.action)?;
+
+            let mut state = self.store.load(chat_id, user_id)?.unwrap_or_default();
+            state.captcha = None;
+            self.store.save(chat_id, user_id, &state)?;
+
+            events.push(ModerationEvent::Violation {
+                chat_id,
+                user_id,
+                violation: Violation::CaptchaExpired,
+                action: rule.action,
+            });
+        }
+
+        Ok(events)
+    }
+
+    /// Returns the first rule `message` breaks, and records it for flood
+    /// detection.
+    fn check(&self, state: &mut MemberState, message: &Message) -> Option<(Violation, Action)> {
+        let date = message.date.timestamp();
+        let mut violations = Vec::new();
+
+        if let Some(action) = self.channel_forwards {
+            if !message.is_automatic_forward()
+                && message
+                    .forward_from_chat()
+                    .is_some_and(|chat| chat.is_channel())
+            {
+                violations.push((Violation::ChannelForward, action));
+            }
+        }
+
+        if let Some(action) = self.links {
+            if state.joined && state.messages == 0 && has_link(message) {
+                violations.push((Violation::LinkInFirstMessage, action));
+            }
+        }
+
+        if let Some(flood) = &self.flood {
+            let since = date - i64::try_from(flood.period.as_secs()).unwrap_or(i64::MAX);
+            state.recent.retain(|&sent| sent > since);
+            state.recent.push(date);
+            if state.recent.len() > flood.max_messages {
+                violations.push((Violation::Flood, flood.action));
+            }
+        }
+
+        violations.into_iter().next()
+    }
+}
+
+/// Mutes `member` and sends them the captcha of `rule`.
+fn send_captcha(
+    tele: &Telegram,
+    message: &Message,
+    member: &User,
+    rule: &CaptchaRule,
+) -> Result<Captcha> {
+    let chat_id = message.chat.id;
+    tele.restrict_chat_member(chat_id, member.id, ChatPermissions::empty())?;
+
+    let text = rule.text.replace("{name}", &member.full_name());
+    let button = InlineKeyboardButton::callback(
+        rule.button.as_str(),
+        format!("{CAPTCHA_PREFIX}{}", member.id),
+    );
+    let sent =
+        tele.send_message_with_reply_markup(chat_id, text, ReplyMarkup::inline_kb([[button]]))?;
+
+    let timeout = i64::try_from(rule.timeout.as_secs()).unwrap_or(i64::MAX);
+    Ok(Captcha {
+        message_id: sent.id,
+        expires_at: message.date.timestamp().saturating_add(timeout),
+    })
+}
+
+/// Returns `true` if the text or caption of `message` has a link.
+fn has_link(message: &Message) -> bool {
+    let is_link = |entity: &MessageEntity| {
+        matches!(
+            entity.kind,
+            MessageEntityKind::Url | MessageEntityKind::TextLink { .. }
+        )
+    };
+
+    let entities = message.entities().or(message.caption_entities());
+    entities.unwrap_or_default().iter().any(is_link)
+}
+
+fn apply(
+    tele: &Telegram,
+    chat_id: ChatId,
+    user_id: UserId,
+    message_id: Option<MessageId>,
+    action: Action,
+) -> Result<()> {
+    if action == Action::Flag {
+        return Ok(());
+    }
+    if let Some(message_id) = message_id {
+        ignore_api_error(tele.delete_message(chat_id, message_id))?;
+    }
+
+    match action {
+        Action::Flag | Action::Delete => {}
+        Action::Mute(duration) => {
+            let until_date = UntilDate::after(duration);
+            let permissions = ChatPermissions::empty();
+            tele.restrict_chat_member_until(chat_id, user_id, permissions, until_date, false)?;
+        }
+        Action::Kick => {
+            tele.ban_chat_member(chat_id, user_id)?;
+            tele.unban_chat_member(chat_id, user_id)?;
+        }
+        Action::Ban => {
+            tele.ban_chat_member(chat_id, user_id)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Ignores the Bot API refusing a call, e.g. deleting a message someone
+/// already deleted, but not other errors.
+fn ignore_api_error<T>(result: Result<T>) -> Result<()> {
+    match result {
+        Err(e) if e.downcast_ref::<ApiError>().is_none() => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::Arc,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use serde_json::{json, Value};
+
+    use crate::{
+        Action, CallbackQuery, ChatId, MemoryModerationStore, Message, ModerationEvent,
+        ModerationStore, Moderator, ReplayTransport, Telegram, UserId, Violation,
+    };
+
+    const CHAT: ChatId = ChatId(-100);
+    const USER: UserId = UserId(7);
+
+    fn message(date: i64, extra: Value) -> Message {
+        let mut message = json!({
+            "message_id": date,
+            "date": date,
+            "chat": { "id": CHAT.0, "type": "supergroup", "title": "Group" },
+            "from": { "id": USER.0, "is_bot": false, "first_name": "Ann" },
+        });
+        message
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(message).unwrap()
+    }
+
+    fn text(date: i64, text: &str) -> Message {
+        message(date, json!({ "text": text }))
+    }
+
+    fn methods(replay: &ReplayTransport) -> Vec<String> {
+        let calls = replay.calls().into_iter();
+        calls.map(|(method, _)| method).collect()
+    }
+
+    fn violation(violation: Violation, action: Action) -> Vec<ModerationEvent> {
+        vec![ModerationEvent::Violation {
+            chat_id: CHAT,
+            user_id: USER,
+            violation,
+            action,
+        }]
+    }
+
+    fn setup() -> (Arc<ReplayTransport>, Telegram) {
+        let replay = Arc::new(ReplayTransport::default());
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+        (replay, tele)
+    }
+
+    #[test]
+    fn link_in_first_message() {
+        let (replay, tele) = setup();
+        let moderator =
+            Moderator::new(MemoryModerationStore::new()).first_message_links(Action::Ban);
+        let link = message(
+            3,
+            json!({
+                "text": "see example.com",
+                "entities": [{ "type": "url", "offset": 4, "length": 11 }],
+            }),
+        );
+
+        let joined = message(
+            1,
+            json!({ "new_chat_members": [{ "id": USER.0, "is_bot": false, "first_name": "Ann" }] }),
+        );
+        assert!(moderator.handle(&tele, &joined).unwrap().is_empty());
+        assert!(moderator.store().load(CHAT, USER).unwrap().unwrap().joined);
+
+        replay.push_result(json!(true));
+        replay.push_result(json!(true));
+        let spam = message(
+            2,
+            json!({
+                "text": "cheap stuff",
+                "entities": [{ "type": "text_link", "offset": 0, "length": 5, "url": "https://spam.example/" }],
+            }),
+        );
+        assert_eq!(
+            moderator.handle(&tele, &spam).unwrap(),
+            violation(Violation::LinkInFirstMessage, Action::Ban)
+        );
+        assert_eq!(methods(&replay), ["deleteMessage", "banChatMember"]);
+
+        assert!(moderator.handle(&tele, &link).unwrap().is_empty());
+
+        // not seen joining, so maybe a member from before
+        let moderator =
+            Moderator::new(MemoryModerationStore::new()).first_message_links(Action::Ban);
+        assert!(moderator.handle(&tele, &link).unwrap().is_empty());
+        assert_eq!(replay.requests().len(), 2);
+    }
+
+    #[test]
+    fn flood() {
+        let (replay, tele) = setup();
+        let mute = Action::Mute(Duration::from_secs(600));
+        let moderator =
+            Moderator::new(MemoryModerationStore::new()).flood(3, Duration::from_secs(10), mute);
+
+        for date in [100, 101, 102] {
+            assert!(moderator
+                .handle(&tele, &text(date, "hi"))
+                .unwrap()
+                .is_empty());
+        }
+        replay.push_result(json!(true));
+        replay.push_result(json!(true));
+        assert_eq!(
+            moderator.handle(&tele, &text(103, "hi")).unwrap(),
+            violation(Violation::Flood, mute)
+        );
+        assert_eq!(methods(&replay), ["deleteMessage", "restrictChatMember"]);
+
+        // the first messages are out of the window by now
+        assert!(moderator
+            .handle(&tele, &text(112, "hi"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn channel_forward() {
+        let (replay, tele) = setup();
+        let moderator =
+            Moderator::new(MemoryModerationStore::new()).channel_forwards(Action::Delete);
+
+        replay.push_error(400, "Bad Request: message to delete not found");
+        let forward = message(
+            1,
+            json!({
+                "text": "join us",
+                "forward_from_chat": { "id": -1001, "type": "channel", "title": "Spam" },
+                "forward_from_message_id": 3,
+                "forward_date": 0,
+            }),
+        );
+        assert_eq!(
+            moderator.handle(&tele, &forward).unwrap(),
+            violation(Violation::ChannelForward, Action::Delete)
+        );
+
+        // a post of the linked channel
+        let post = message(
+            2,
+            json!({
+                "text": "new release",
+                "forward_from_chat": { "id": -1002, "type": "channel", "title": "News" },
+                "forward_from_message_id": 4,
+                "forward_date": 0,
+                "is_automatic_forward": true,
+            }),
+        );
+        assert!(moderator.handle(&tele, &post).unwrap().is_empty());
+        assert_eq!(methods(&replay), ["deleteMessage"]);
+    }
+
+    #[test]
+    fn captcha() {
+        let (replay, tele) = setup();
+        let store = MemoryModerationStore::new();
+        let moderator = Moderator::new(&store).captcha(Duration::from_secs(60), Action::Kick);
+
+        let new_member = |date| {
+            message(
+                date,
+                json!({ "new_chat_members": [{ "id": USER.0, "is_bot": false, "first_name": "Ann" }] }),
+            )
+        };
+        let captcha_message = json!({
+            "message_id": 50,
+            "date": 0,
+            "chat": { "id": CHAT.0, "type": "supergroup", "title": "Group" },
+            "text": "Welcome, Ann! Press the button below to start writing.",
+        });
+        let press = |user_id: u64| -> CallbackQuery {
+            serde_json::from_value(json!({
+                "id": "cq",
+                "from": { "id": user_id, "is_bot": false, "first_name": "Bob" },
+                "message": captcha_message,
+                "chat_instance": "ci",
+                "data": "captcha:7",
+            }))
+            .unwrap()
+        };
+
+        replay.push_result(json!(true));
+        replay.push_result(captcha_message.clone());
+        let sent = ModerationEvent::CaptchaSent {
+            chat_id: CHAT,
+            user_id: USER,
+        };
+        assert_eq!(moderator.handle(&tele, &new_member(1000)).unwrap(), [sent]);
+
+        replay.push_result(json!(true));
+        assert_eq!(moderator.handle_callback(&tele, &press(8)).unwrap(), None);
+        assert_eq!(store.pending_captchas().unwrap().len(), 1);
+
+        for _ in 0..3 {
+            replay.push_result(json!(true));
+        }
+        assert_eq!(
+            moderator.handle_callback(&tele, &press(7)).unwrap(),
+            Some(ModerationEvent::CaptchaSolved {
+                chat_id: CHAT,
+                user_id: USER
+            })
+        );
+        assert!(store.pending_captchas().unwrap().is_empty());
+
+        let requests = replay.requests();
+        let body = |i: usize| serde_json::from_slice::<Value>(&requests[i].body).unwrap();
+        assert_eq!(body(0)["permissions"]["can_send_messages"], Value::Null);
+        assert_eq!(
+            body(1)["reply_markup"]["inline_keyboard"][0][0]["callback_data"],
+            "captcha:7"
+        );
+        assert_eq!(body(2)["text"], "This is not for you");
+        assert_eq!(body(3)["permissions"]["can_send_messages"], true);
+
+        // a second member who never presses the button
+        replay.push_result(json!(true));
+        replay.push_result(captcha_message.clone());
+        moderator.handle(&tele, &new_member(2000)).unwrap();
+
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        assert!(moderator
+            .expire_captchas(&tele, at(2059))
+            .unwrap()
+            .is_empty());
+        for _ in 0..3 {
+            replay.push_result(json!(true));
+        }
+        assert_eq!(
+            moderator.expire_captchas(&tele, at(2060)).unwrap(),
+            violation(Violation::CaptchaExpired, Action::Kick)
+        );
+        let methods = methods(&replay);
+        assert_eq!(
+            methods[methods.len() - 3..],
+            ["deleteMessage", "banChatMember", "unbanChatMember"]
+        );
+        assert!(store.pending_captchas().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt::Write;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::InputFile;
+
+/// Encodes the payload of a call uploading `files`, returning its
+/// `Content-Type` and body.
+///
+/// `fields` must be a JSON object which refers to each file as
+/// `attach://<id>`, as [`InputFile`] serializes. Calls with nothing to upload
+/// are sent as plain JSON. `null` fields are left out either way.
+pub(crate) fn encode(fields: &Value, files: Vec<InputFile>) -> Result<(String, Vec<u8>)> {
+    let files: Vec<_> = files.into_iter().filter(InputFile::needs_attach).collect();
+    if files.is_empty() {
+        let mut fields = fields.clone();
+        if let Value::Object(fields) = &mut fields {
+            fields.retain(|_, value| !value.is_null());
+        }
+        return Ok((
+            String::from("application/json"),
+            fields.to_string().into_bytes(),
+        ));
+    }
+
+    let boundary = uuid::Uuid::new_v4().as_simple().to_string();
+    let mut body = Vec::new();
+
+    for (name, value) in fields.as_object().into_iter().flatten() {
+        let value = match value {
+            Value::Null => continue,
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+
+        let mut head = format!("--{boundary}\r\n");
+        write!(
+            head,
+            "Content-Disposition: form-data; name=\"{name}\"\r\n\r\n"
+        )
+        .unwrap();
+        body.extend_from_slice(head.as_bytes());
+        body.extend_from_slice(value.as_bytes());
+        body.extend_from_slice(b"\r\n");
+    }
+
+    for mut file in files {
+        let contents = file
+            .contents()
+            .with_context(|| format!("can't read {file:?}"))?;
+        let file_name = file.take_or_guess_filename().replace('"', "%22");
+
+        let mut head = format!("--{boundary}\r\n");
+        write!(
+            head,
+            "Content-Disposition: form-data; name=\"{}\"; filename=\"{file_name}\"\r\n",
+            file.id()
+        )
+        .unwrap();
+        head += "Content-Type: application/octet-stream\r\n\r\n";
+        body.extend_from_slice(head.as_bytes());
+        body.extend_from_slice(&contents);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+    Ok((format!("multipart/form-data; boundary={boundary}"), body))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::encode;
+    use crate::InputFile;
+
+    #[test]
+    fn json_without_uploads() {
+        let file = InputFile::file_id("CAACAgIAAxkB");
+        let fields = json!({ "chat_id": 1, "sticker": file, "emoji": null });
+
+        let (content_type, body) = encode(&fields, vec![file]).unwrap();
+        assert_eq!(content_type, "application/json");
+        assert_eq!(body, br#"{"chat_id":1,"sticker":"CAACAgIAAxkB"}"#);
+    }
+
+    #[test]
+    fn form_with_uploads() {
+        let file = InputFile::memory(&b"PNG"[..]).file_name("cat.png");
+        let fields =
+            json!({ "user_id": 7, "png_sticker": file, "emojis": "🐱", "mask_position": null });
+        let id = file.id().to_owned();
+
+        let (content_type, body) = encode(&fields, vec![file]).unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let expected = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"emojis\"\r\n\r\n🐱\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"png_sticker\"\r\n\r\nattach://{id}\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"user_id\"\r\n\r\n7\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"{id}\"; filename=\"cat.png\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\nPNG\r\n\
+             --{boundary}--\r\n"
+        );
+        assert_eq!(String::from_utf8(body).unwrap(), expected);
+    }
+}
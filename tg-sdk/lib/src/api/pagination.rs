@@ -0,0 +1,295 @@
+use anyhow::Result;
+
+use crate::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Telegram};
+
+/// Lays a long list of buttons out in pages, with a row to move between
+/// them.
+///
+/// The page to show is in the callback data of the navigation buttons, as
+/// `<prefix>:<page>`, so the prefix has to tell paginated keyboards apart
+/// from each other and other buttons.
+///
+/// ```no_run
+/// use tg_flows::{CallbackQuery, ChatId, InlineKeyboardButton, Paginator, ReplyMarkup, Telegram};
+///
+/// fn items() -> Vec<InlineKeyboardButton> {
+///     (1..=50)
+///         .map(|n| InlineKeyboardButton::callback(format!("Item {n}"), format!("item:{n}")))
+///         .collect()
+/// }
+///
+/// # fn run(tele: Telegram, query: CallbackQuery) -> anyhow::Result<()> {
+/// let paginator = Paginator::new("items").per_page(9).columns(3);
+///
+/// let markup = ReplyMarkup::InlineKeyboard(paginator.markup(items(), 0));
+/// tele.send_message_with_reply_markup(ChatId(42), "Pick one", markup)?;
+///
+/// // later, for every callback query
+/// if !paginator.handle(&tele, &query, items())? {
+///     // not a navigation button, e.g. "item:7"
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Paginator {
+    prefix: String,
+    per_page: usize,
+    columns: usize,
+    prev: String,
+    next: String,
+    page: String,
+}
+
+impl Paginator {
+    /// Creates a paginator showing 10 buttons per page in 2 columns.
+    pub fn new<P>(prefix: P) -> Self
+    where
+        P: Into<String>,
+    {
+        Self {
+            prefix: prefix.into(),
+            per_page: 10,
+            columns: 2,
+            prev: String::from("◀ Prev"),
+            next: String::from("Next ▶"),
+            page: String::from("Page {page}/{pages}"),
+        }
+    }
+
+    #[must_use]
+    pub fn per_page(mut self, per_page: usize) -> Self {
+        self.per_page = per_page.max(1);
+        self
+    }
+
+    #[must_use]
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.columns = columns.max(1);
+        self
+    }
+
+    /// Changes the labels of the buttons to the previous and next page.
+    #[must_use]
+    pub fn labels<P, N>(mut self, prev: P, next: N) -> Self
+    where
+        P: Into<String>,
+        N: Into<String>,
+    {
+        self.prev = prev.into();
+        self.next = next.into();
+        self
+    }
+
+    /// Changes the label between the navigation buttons, where `{page}` is
+    /// replaced with the number of the page, counted from 1, and `{pages}`
+    /// with the number of pages.
+    #[must_use]
+    pub fn page_label<L>(mut self, label: L) -> Self
+    where
+        L: Into<String>,
+    {
+        self.page = label.into();
+        self
+    }
+
+    /// Returns the number of pages `len` buttons take.
+    #[must_use]
+    pub fn pages(&self, len: usize) -> usize {
+        len.div_ceil(self.per_page).max(1)
+    }
+
+    /// Returns the buttons of `page`, counted from 0, followed by a row to
+    /// move between pages if there are more than one.
+    ///
+    /// Pages past the last one show the last one.
+    pub fn markup<I>(&self, buttons: I, page: usize) -> InlineKeyboardMarkup
+    where
+        I: IntoIterator<Item = InlineKeyboardButton>,
+    {
+        let buttons: Vec<_> = buttons.into_iter().collect();
+        let pages = self.pages(buttons.len());
+        let page = page.min(pages - 1);
+
+        let shown = buttons
+            .into_iter()
+            .skip(page * self.per_page)
+            .take(self.per_page);
+        let markup = InlineKeyboardMarkup::grid(shown, self.columns);
+        if pages == 1 {
+            return markup;
+        }
+
+        let prefix = &self.prefix;
+        let mut navigation = Vec::new();
+        if page > 0 {
+            let data = format!("{prefix}:{}", page - 1);
+            navigation.push(InlineKeyboardButton::callback(self.prev.as_str(), data));
+        }
+        let label = self
+            .page
+            .replace("{page}", &(page + 1).to_string())
+            .replace("{pages}", &pages.to_string());
+        navigation.push(InlineKeyboardButton::callback(label, format!("{prefix}:-")));
+        if page + 1 < pages {
+            let data = format!("{prefix}:{}", page + 1);
+            navigation.push(InlineKeyboardButton::callback(self.next.as_str(), data));
+        }
+
+        markup.append_row(navigation)
+    }
+
+    /// Returns the page a navigation button of this paginator shows, or
+    /// `None` for other queries and the page label.
+    #[must_use]
+    pub fn page_of(&self, query: &CallbackQuery) -> Option<usize> {
+        self.parse(query)?.ok()
+    }
+
+    /// Answers `query` if it comes from a navigation button of this
+    /// paginator, and shows the page it asks for in the message of the
+    /// button, built from all the `buttons`.
+    ///
+    /// Returns `Ok(false)` without answering other queries.
+    pub fn handle<I>(&self, tele: &Telegram, query: &CallbackQuery, buttons: I) -> Result<bool>
+    where
+        I: IntoIterator<Item = InlineKeyboardButton>,
+    {
+        let Some(page) = self.parse(query) else {
+            return Ok(false);
+        };
+
+        if let (Ok(page), Some(message)) = (page, &query.message) {
+            let markup = self.markup(buttons, page);
+            tele.edit_message_reply_markup(message.chat.id, message.id, Some(markup))?;
+        }
+        tele.answer_callback_query(query.id.as_str())?;
+        Ok(true)
+    }
+
+    /// Returns `Some(Err(()))` for the page label, which doesn't navigate.
+    fn parse(&self, query: &CallbackQuery) -> Option<Result<usize, ()>> {
+        let data = query.data.as_deref()?.strip_prefix(self.prefix.as_str())?;
+        match data.strip_prefix(':')? {
+            "-" => Some(Err(())),
+            page => page.parse().ok().map(Ok),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use crate::{
+        CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, Paginator, ReplayTransport,
+        Telegram,
+    };
+
+    fn items(n: usize) -> Vec<InlineKeyboardButton> {
+        (1..=n)
+            .map(|i| InlineKeyboardButton::callback(i.to_string(), format!("item:{i}")))
+            .collect()
+    }
+
+    fn labels(markup: &InlineKeyboardMarkup) -> Vec<Vec<&str>> {
+        let rows = markup.inline_keyboard.iter();
+        rows.map(|row| row.iter().map(|button| button.text.as_str()).collect())
+            .collect()
+    }
+
+    fn query(data: &str) -> CallbackQuery {
+        serde_json::from_value(json!({
+            "id": "cq",
+            "from": { "id": 7, "is_bot": false, "first_name": "Ann" },
+            "message": {
+                "message_id": 5,
+                "date": 0,
+                "chat": { "id": 42, "type": "private", "first_name": "Ann" },
+                "text": "Pick one",
+            },
+            "chat_instance": "ci",
+            "data": data,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn markup() {
+        let paginator = Paginator::new("p").per_page(4).columns(3);
+        assert_eq!(paginator.pages(9), 3);
+        assert_eq!(paginator.pages(0), 1);
+
+        assert_eq!(
+            labels(&paginator.markup(items(9), 0)),
+            [vec!["1", "2", "3"], vec!["4"], vec!["Page 1/3", "Next ▶"]]
+        );
+        assert_eq!(
+            labels(&paginator.markup(items(9), 1)),
+            [
+                vec!["5", "6", "7"],
+                vec!["8"],
+                vec!["◀ Prev", "Page 2/3", "Next ▶"]
+            ]
+        );
+        assert_eq!(
+            labels(&paginator.markup(items(9), 7)),
+            [vec!["9"], vec!["◀ Prev", "Page 3/3"]]
+        );
+        assert_eq!(labels(&paginator.markup(items(2), 0)), [vec!["1", "2"]]);
+
+        let paginator = paginator.labels("<", ">").page_label("{page} of {pages}");
+        assert_eq!(
+            labels(&paginator.markup(items(9), 1))[2],
+            ["<", "2 of 3", ">"]
+        );
+
+        let markup = paginator.markup(items(9), 1);
+        let navigation = &markup.inline_keyboard[2];
+        let data =
+            |i: usize| serde_json::to_value(&navigation[i]).unwrap()["callback_data"].clone();
+        assert_eq!([data(0), data(1), data(2)], ["p:0", "p:-", "p:2"]);
+    }
+
+    #[test]
+    fn handle() {
+        let replay = Arc::new(ReplayTransport::default());
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+        let paginator = Paginator::new("p").per_page(4);
+
+        assert_eq!(paginator.page_of(&query("p:2")), Some(2));
+        assert_eq!(paginator.page_of(&query("p:-")), None);
+        assert!(!paginator.handle(&tele, &query("item:3"), items(9)).unwrap());
+        assert!(!paginator.handle(&tele, &query("px:1"), items(9)).unwrap());
+
+        replay.push_result(json!({
+            "message_id": 5,
+            "date": 0,
+            "chat": { "id": 42, "type": "private", "first_name": "Ann" },
+            "text": "Pick one",
+        }));
+        replay.push_result(json!(true));
+        assert!(paginator.handle(&tele, &query("p:1"), items(9)).unwrap());
+
+        replay.push_result(json!(true));
+        assert!(paginator.handle(&tele, &query("p:-"), items(9)).unwrap());
+
+        let calls = replay.calls();
+        let methods: Vec<_> = calls.iter().map(|(method, _)| method.as_str()).collect();
+        assert_eq!(
+            methods,
+            [
+                "editMessageReplyMarkup",
+                "answerCallbackQuery",
+                "answerCallbackQuery"
+            ]
+        );
+
+        let body = &calls[0].1;
+        assert_eq!(body["chat_id"], 42);
+        assert_eq!(body["message_id"], 5);
+        assert_eq!(body["reply_markup"]["inline_keyboard"][0][0]["text"], "5");
+    }
+}
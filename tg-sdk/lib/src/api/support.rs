@@ -0,0 +1,425 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{ApiError, ChatId, Message, MessageKind, Telegram};
+
+/// The forum topic a user's conversation is mirrored into.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Ticket {
+    /// The private chat with the user.
+    pub user_chat_id: ChatId,
+
+    /// The topic in the staff supergroup.
+    pub message_thread_id: i32,
+
+    pub is_closed: bool,
+}
+
+/// Where a [`SupportDesk`] keeps its tickets.
+pub trait TicketStore {
+    fn by_user(&self, user_chat_id: ChatId) -> Result<Option<Ticket>>;
+
+    fn by_topic(&self, message_thread_id: i32) -> Result<Option<Ticket>>;
+
+    /// Saves `ticket`, replacing any other ticket of the same user.
+    fn save(&self, ticket: &Ticket) -> Result<()>;
+}
+
+impl<S> TicketStore for &S
+where
+    S: TicketStore + ?Sized,
+{
+    fn by_user(&self, user_chat_id: ChatId) -> Result<Option<Ticket>> {
+        (**self).by_user(user_chat_id)
+    }
+
+    fn by_topic(&self, message_thread_id: i32) -> Result<Option<Ticket>> {
+        (**self).by_topic(message_thread_id)
+    }
+
+    fn save(&self, ticket: &Ticket) -> Result<()> {
+        (**self).save(ticket)
+    }
+}
+
+/// A [`TicketStore`] that lives as long as the process does.
+#[derive(Debug, Default)]
+pub struct MemoryTicketStore {
+    tickets: Mutex<HashMap<ChatId, Ticket>>,
+}
+
+impl MemoryTicketStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TicketStore for MemoryTicketStore {
+    fn by_user(&self, user_chat_id: ChatId) -> Result<Option<Ticket>> {
+        Ok(self.tickets.lock().unwrap().get(&user_chat_id).cloned())
+    }
+
+    fn by_topic(&self, message_thread_id: i32) -> Result<Option<Ticket>> {
+        let tickets = self.tickets.lock().unwrap();
+        let ticket = tickets
+            .values()
+            .find(|ticket| ticket.message_thread_id == message_thread_id);
+        Ok(ticket.cloned())
+    }
+
+    fn save(&self, ticket: &Ticket) -> Result<()> {
+        self.tickets
+            .lock()
+            .unwrap()
+            .insert(ticket.user_chat_id, ticket.clone());
+        Ok(())
+    }
+}
+
+/// What [`SupportDesk::handle`] did with a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SupportEvent {
+    /// A topic was created for the user and their message copied into it.
+    Opened(Ticket),
+
+    /// The user's message was copied into their topic.
+    FromUser(Ticket),
+
+    /// A staff message was copied to the user.
+    ToUser(Ticket),
+
+    /// The topic was closed by a staff command.
+    Closed(Ticket),
+
+    /// The topic was reopened by a staff command or by the user writing
+    /// again, in which case their message was copied into it.
+    Reopened(Ticket),
+
+    /// The message isn't part of a ticket, e.g. it was sent to the General
+    /// topic or is a service message.
+    Ignored,
+}
+
+/// Mirrors private conversations with the bot into a forum supergroup of
+/// staff, one topic per user.
+///
+/// Messages users send the bot are copied into their topic, which is created
+/// on their first message, and messages staff send in the topic are copied
+/// back to the user. Staff close and reopen a topic with the `/close` and
+/// `/reopen` commands, and a user writing again reopens it too.
+///
+/// The bot must be an admin of the supergroup, allowed to manage topics.
+///
+/// ```no_run
+/// use tg_flows::{ChatId, MemoryTicketStore, Message, SupportDesk, Telegram};
+///
+/// # fn run(tele: Telegram, message: Message) -> anyhow::Result<()> {
+/// let desk = SupportDesk::new(ChatId(-100123), MemoryTicketStore::new());
+/// desk.handle(&tele, &message)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SupportDesk<S> {
+    staff_chat_id: ChatId,
+    store: S,
+    close_command: String,
+    reopen_command: String,
+}
+
+impl<S> SupportDesk<S>
+where
+    S: TicketStore,
+{
+    pub fn new(staff_chat_id: ChatId, store: S) -> Self {
+        Self {
+            staff_chat_id,
+            store,
+            close_command: String::from("/close"),
+            reopen_command: String::from("/reopen"),
+        }
+    }
+
+    /// Closes topics on `command` instead of `/close`.
+    pub fn close_command<C>(mut self, command: C) -> Self
+    where
+        C: Into<String>,
+    {
+        self.close_command = command.into();
+        self
+    }
+
+    /// Reopens topics on `command` instead of `/reopen`.
+    pub fn reopen_command<C>(mut self, command: C) -> Self
+    where
+        C: Into<String>,
+    {
+        self.reopen_command = command.into();
+        self
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Relays `message` if it's a private message to the bot or a message in
+    /// a ticket's topic, or runs the staff command it contains.
+    pub fn handle(&self, tele: &Telegram, message: &Message) -> Result<SupportEvent> {
+        if !matches!(message.kind, MessageKind::Common(_)) {
+            return Ok(SupportEvent::Ignored);
+        }
+
+        if message.chat.id == self.staff_chat_id {
+            self.staff_message(tele, message)
+        } else if message.chat.is_private() {
+            self.user_message(tele, message)
+        } else {
+            Ok(SupportEvent::Ignored)
+        }
+    }
+
+    fn user_message(&self, tele: &Telegram, message: &Message) -> Result<SupportEvent> {
+        let ticket = match self.store.by_user(message.chat.id)? {
+            None => return self.open(tele, message),
+            Some(ticket) if ticket.is_closed => {
+                match tele.reopen_forum_topic(self.staff_chat_id, ticket.message_thread_id) {
+                    Ok(_) => {}
+                    Err(e) if is_gone(&e) => return self.open(tele, message),
+                    Err(e) => return Err(e),
+                }
+                let ticket = Ticket {
+                    is_closed: false,
+                    ..ticket
+                };
+                self.store.save(&ticket)?;
+                self.copy_to_topic(tele, message, &ticket)?;
+                return Ok(SupportEvent::Reopened(ticket));
+            }
+            Some(ticket) => ticket,
+        };
+
+        match self.copy_to_topic(tele, message, &ticket) {
+            Ok(()) => Ok(SupportEvent::FromUser(ticket)),
+            // staff deleted the topic, start over in a new one
+            Err(e) if is_gone(&e) => self.open(tele, message),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn open(&self, tele: &Telegram, message: &Message) -> Result<SupportEvent> {
+        let topic = tele.create_forum_topic(self.staff_chat_id, topic_name(message), None, None)?;
+        let ticket = Ticket {
+            user_chat_id: message.chat.id,
+            message_thread_id: topic.message_thread_id,
+            is_closed: false,
+        };
+        self.store.save(&ticket)?;
+        self.copy_to_topic(tele, message, &ticket)?;
+
+        Ok(SupportEvent::Opened(ticket))
+    }
+
+    fn copy_to_topic(&self, tele: &Telegram, message: &Message, ticket: &Ticket) -> Result<()> {
+        tele.in_thread(ticket.message_thread_id).copy_message(
+            self.staff_chat_id,
+            message.chat.id,
+            message.id,
+        )?;
+        Ok(())
+    }
+
+    fn staff_message(&self, tele: &Telegram, message: &Message) -> Result<SupportEvent> {
+        let ticket = match message.thread_id {
+            Some(message_thread_id) => self.store.by_topic(message_thread_id)?,
+            None => None,
+        };
+        let Some(ticket) = ticket else {
+            return Ok(SupportEvent::Ignored);
+        };
+
+        let command = message
+            .text()
+            .and_then(|text| text.split_whitespace().next());
+        let command = command.map(|c| c.split('@').next().unwrap_or(c));
+
+        if command == Some(self.close_command.as_str()) {
+            tele.close_forum_topic(self.staff_chat_id, ticket.message_thread_id)?;
+            let ticket = Ticket {
+                is_closed: true,
+                ..ticket
+            };
+            self.store.save(&ticket)?;
+            Ok(SupportEvent::Closed(ticket))
+        } else if command == Some(self.reopen_command.as_str()) {
+            tele.reopen_forum_topic(self.staff_chat_id, ticket.message_thread_id)?;
+            let ticket = Ticket {
+                is_closed: false,
+                ..ticket
+            };
+            self.store.save(&ticket)?;
+            Ok(SupportEvent::Reopened(ticket))
+        } else {
+            tele.copy_message(ticket.user_chat_id, self.staff_chat_id, message.id)?;
+            Ok(SupportEvent::ToUser(ticket))
+        }
+    }
+}
+
+/// Names a ticket's topic after the user, topic names are at most 128
+/// characters.
+fn topic_name(message: &Message) -> String {
+    let name = match message.from() {
+        Some(user) => match &user.username {
+            Some(username) => format!("{} (@{username})", user.full_name()),
+            None => user.full_name(),
+        },
+        None => message.chat.id.to_string(),
+    };
+
+    name.chars().take(128).collect()
+}
+
+/// Returns `true` if the topic of a ticket doesn't exist anymore.
+fn is_gone(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<ApiError>().is_some_and(|e| {
+        let description = e.description.to_lowercase();
+        description.contains("thread not found") || description.contains("topic_id_invalid")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::{json, Value};
+
+    use crate::{
+        ChatId, MemoryTicketStore, Message, ReplayTransport, SupportDesk, SupportEvent, Telegram,
+        Ticket, TicketStore,
+    };
+
+    const STAFF: ChatId = ChatId(-100);
+    const USER: ChatId = ChatId(7);
+
+    fn message(chat: Value, thread_id: Option<i32>, text: &str) -> Message {
+        serde_json::from_value(json!({
+            "message_id": 1,
+            "message_thread_id": thread_id,
+            "date": 0,
+            "chat": chat,
+            "from": { "id": 7, "is_bot": false, "first_name": "Ann", "username": "ann" },
+            "text": text,
+        }))
+        .unwrap()
+    }
+
+    fn from_user(text: &str) -> Message {
+        let chat = json!({ "id": USER.0, "type": "private", "first_name": "Ann" });
+        message(chat, None, text)
+    }
+
+    fn from_staff(thread_id: Option<i32>, text: &str) -> Message {
+        let chat =
+            json!({ "id": STAFF.0, "type": "supergroup", "title": "Staff", "is_forum": true });
+        message(chat, thread_id, text)
+    }
+
+    fn ticket(is_closed: bool) -> Ticket {
+        Ticket {
+            user_chat_id: USER,
+            message_thread_id: 4,
+            is_closed,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let replay = Arc::new(ReplayTransport::default());
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+        let desk = SupportDesk::new(STAFF, MemoryTicketStore::new());
+
+        replay.push_result(
+            json!({ "message_thread_id": 4, "name": "Ann (@ann)", "icon_color": 7322096 }),
+        );
+        replay.push_result(json!({ "message_id": 10 }));
+        let event = desk.handle(&tele, &from_user("help")).unwrap();
+        assert_eq!(event, SupportEvent::Opened(ticket(false)));
+
+        replay.push_result(json!({ "message_id": 11 }));
+        let event = desk.handle(&tele, &from_user("please")).unwrap();
+        assert_eq!(event, SupportEvent::FromUser(ticket(false)));
+
+        replay.push_result(json!({ "message_id": 2 }));
+        let event = desk.handle(&tele, &from_staff(Some(4), "on it")).unwrap();
+        assert_eq!(event, SupportEvent::ToUser(ticket(false)));
+
+        replay.push_result(json!(true));
+        let event = desk
+            .handle(&tele, &from_staff(Some(4), "/close@bot"))
+            .unwrap();
+        assert_eq!(event, SupportEvent::Closed(ticket(true)));
+
+        let event = desk.handle(&tele, &from_staff(None, "hello")).unwrap();
+        assert_eq!(event, SupportEvent::Ignored);
+
+        replay.push_result(json!(true));
+        replay.push_result(json!({ "message_id": 12 }));
+        let event = desk.handle(&tele, &from_user("again")).unwrap();
+        assert_eq!(event, SupportEvent::Reopened(ticket(false)));
+        assert_eq!(desk.store().by_topic(4).unwrap(), Some(ticket(false)));
+
+        let methods = replay.calls();
+        let names: Vec<_> = methods.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "createForumTopic",
+                "copyMessage",
+                "copyMessage",
+                "copyMessage",
+                "closeForumTopic",
+                "reopenForumTopic",
+                "copyMessage",
+            ]
+        );
+        assert_eq!(
+            methods[0].1,
+            json!({ "chat_id": STAFF.0, "name": "Ann (@ann)" })
+        );
+        assert_eq!(
+            methods[1].1,
+            json!({ "chat_id": STAFF.0, "from_chat_id": USER.0, "message_id": 1, "message_thread_id": 4 })
+        );
+        assert_eq!(
+            methods[3].1,
+            json!({ "chat_id": USER.0, "from_chat_id": STAFF.0, "message_id": 1 })
+        );
+    }
+
+    #[test]
+    fn reopens_deleted_topic_as_new() {
+        let replay = Arc::new(ReplayTransport::default());
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+        let store = MemoryTicketStore::new();
+        store.save(&ticket(false)).unwrap();
+        let desk = SupportDesk::new(STAFF, &store);
+
+        replay.push_error(400, "Bad Request: message thread not found");
+        replay.push_result(
+            json!({ "message_thread_id": 9, "name": "Ann (@ann)", "icon_color": 7322096 }),
+        );
+        replay.push_result(json!({ "message_id": 10 }));
+        let event = desk.handle(&tele, &from_user("hello?")).unwrap();
+
+        let reopened = Ticket {
+            message_thread_id: 9,
+            ..ticket(false)
+        };
+        assert_eq!(event, SupportEvent::Opened(reopened.clone()));
+        assert_eq!(store.by_user(USER).unwrap(), Some(reopened));
+        assert_eq!(store.by_topic(4).unwrap(), None);
+    }
+}
@@ -0,0 +1,166 @@
+#[cfg(feature = "async")]
+use std::future::Future;
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+#[cfg(feature = "async")]
+use crate::AsyncTelegram;
+use crate::{InputFile, Message, Method, Telegram};
+
+/// Sends messages into a forum topic, or the thread of replies to a message.
+///
+/// It has the `send_*` methods of the client it was created from, which pass
+/// `message_thread_id` along:
+///
+/// ```no_run
+/// # fn main() -> anyhow::Result<()> {
+/// use tg_flows::{ChatId, Telegram};
+///
+/// let tele = Telegram::new(std::env::var("TOKEN")?);
+/// let topic = tele.create_forum_topic(ChatId(-100123), "Ticket #42", None, None)?;
+/// tele.in_thread(topic.message_thread_id)
+///     .send_message(ChatId(-100123), "New ticket")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct InThread<'a, C> {
+    client: &'a C,
+    message_thread_id: i32,
+}
+
+impl<C> InThread<'_, C> {
+    #[must_use]
+    pub fn message_thread_id(&self) -> i32 {
+        self.message_thread_id
+    }
+
+    /// Adds `message_thread_id` to a JSON `body`.
+    fn with_thread(&self, mut body: Value) -> Value {
+        if let Value::Object(fields) = &mut body {
+            fields.insert("message_thread_id".into(), self.message_thread_id.into());
+        }
+        body
+    }
+
+    fn with_thread_bytes(&self, body: &[u8]) -> Vec<u8> {
+        match serde_json::from_slice(body) {
+            Ok(body) => self.with_thread(body).to_string().into_bytes(),
+            Err(_) => body.to_vec(),
+        }
+    }
+}
+
+impl Telegram {
+    /// Returns a handle sending messages into the thread `message_thread_id`.
+    #[must_use]
+    pub fn in_thread(&self, message_thread_id: i32) -> InThread<'_, Self> {
+        InThread {
+            client: self,
+            message_thread_id,
+        }
+    }
+}
+
+impl InThread<'_, Telegram> {
+    pub fn request<T>(&self, method: Method, body: &[u8]) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.client.request(method, &self.with_thread_bytes(body))
+    }
+
+    pub fn request_multipart<T>(
+        &self,
+        method: Method,
+        fields: Value,
+        files: Vec<InputFile>,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.client
+            .request_multipart(method, self.with_thread(fields), files)
+    }
+
+    pub fn request_replies(&self, method: Method, bodies: Vec<Value>) -> Result<Vec<Message>> {
+        let bodies = bodies.into_iter().map(|body| self.with_thread(body));
+        self.client.request_replies(method, bodies.collect())
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTelegram {
+    /// Returns a handle sending messages into the thread `message_thread_id`.
+    #[must_use]
+    pub fn in_thread(&self, message_thread_id: i32) -> InThread<'_, Self> {
+        InThread {
+            client: self,
+            message_thread_id,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a> InThread<'a, AsyncTelegram> {
+    pub fn request<T>(&self, method: Method, body: &[u8]) -> impl Future<Output = Result<T>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+    {
+        self.client.request(method, &self.with_thread_bytes(body))
+    }
+
+    pub fn request_multipart<T>(
+        &self,
+        method: Method,
+        fields: Value,
+        files: Vec<InputFile>,
+    ) -> impl Future<Output = Result<T>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+    {
+        self.client
+            .request_multipart(method, self.with_thread(fields), files)
+    }
+
+    pub fn request_replies(
+        &self,
+        method: Method,
+        bodies: Vec<Value>,
+    ) -> impl Future<Output = Result<Vec<Message>>> + 'a {
+        let bodies = bodies.into_iter().map(|body| self.with_thread(body));
+        self.client.request_replies(method, bodies.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::{json, Value};
+
+    use crate::{ChatId, ReplayTransport, Telegram};
+
+    #[test]
+    fn adds_thread_id() {
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push_result(json!({
+            "message_id": 5,
+            "message_thread_id": 4,
+            "date": 1675229140,
+            "chat": { "id": -100, "type": "supergroup", "title": "Support", "is_forum": true },
+            "text": "hi",
+        }));
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        let message = tele.in_thread(4).send_message(ChatId(-100), "hi").unwrap();
+        assert_eq!(message.thread_id, Some(4));
+
+        let body: Value = serde_json::from_slice(&replay.requests()[0].body).unwrap();
+        assert_eq!(
+            body,
+            json!({ "chat_id": -100, "text": "hi", "message_thread_id": 4 })
+        );
+    }
+}
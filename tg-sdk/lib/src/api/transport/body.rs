@@ -0,0 +1,119 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+
+use super::HttpResponse;
+
+/// How much of a response body is held back before writing it through, Bot
+/// API errors are well under it.
+const HELD_BACK: usize = 4096;
+
+/// A response body writer for [`Transport::send_to`] on HTTP clients which
+/// only report the status once the body has been written.
+///
+/// The body is written through to the caller's writer once it's longer than
+/// [`HELD_BACK`], so that a short error response is returned whole by
+/// [`finish`](BodyWriter::finish) without writing it. Longer error responses
+/// are written and only their start is returned.
+///
+/// [`Transport::send_to`]: super::Transport::send_to
+pub(crate) struct BodyWriter<'a> {
+    inner: &'a mut dyn Write,
+    held: Vec<u8>,
+    passed_through: bool,
+}
+
+impl<'a> BodyWriter<'a> {
+    pub(crate) fn new(inner: &'a mut dyn Write) -> Self {
+        Self {
+            inner,
+            held: Vec::new(),
+            passed_through: false,
+        }
+    }
+
+    /// Returns the response with `status`, writing what's still held back of
+    /// a successful body.
+    pub(crate) fn finish(self, status: u16) -> Result<HttpResponse> {
+        if !(200..300).contains(&status) {
+            return Ok(HttpResponse::new(status, self.held));
+        }
+
+        if !self.passed_through {
+            self.inner.write_all(&self.held)?;
+        }
+        Ok(HttpResponse::new(status, Vec::new()))
+    }
+}
+
+impl Write for BodyWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.passed_through {
+            return self.inner.write(buf);
+        }
+
+        self.held.extend_from_slice(buf);
+        if self.held.len() > HELD_BACK {
+            self.inner.write_all(&self.held)?;
+            self.held.truncate(HELD_BACK);
+            self.passed_through = true;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::{BodyWriter, HELD_BACK};
+
+    #[test]
+    fn writes_successful_bodies() {
+        let mut file = Vec::new();
+        let mut body = BodyWriter::new(&mut file);
+        body.write_all(b"hello").unwrap();
+        let response = body.finish(200).unwrap();
+
+        assert_eq!(file, b"hello");
+        assert!(response.body.is_empty());
+
+        let contents = vec![7; HELD_BACK * 3];
+        let mut file = Vec::new();
+        let mut body = BodyWriter::new(&mut file);
+        for chunk in contents.chunks(1000) {
+            body.write_all(chunk).unwrap();
+        }
+        let response = body.finish(200).unwrap();
+
+        assert_eq!(file, contents);
+        assert!(response.body.is_empty());
+    }
+
+    #[test]
+    fn returns_error_bodies() {
+        let error = br#"{"ok":false,"error_code":404,"description":"Not Found"}"#;
+        let mut file = Vec::new();
+        let mut body = BodyWriter::new(&mut file);
+        body.write_all(error).unwrap();
+        let response = body.finish(404).unwrap();
+
+        assert!(file.is_empty());
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body, error);
+
+        // too long to hold back
+        let page = vec![b'x'; HELD_BACK + 1];
+        let mut file = Vec::new();
+        let mut body = BodyWriter::new(&mut file);
+        body.write_all(&page).unwrap();
+        let response = body.finish(502).unwrap();
+
+        assert_eq!(file, page);
+        assert_eq!(response.body, &page[..HELD_BACK]);
+    }
+}
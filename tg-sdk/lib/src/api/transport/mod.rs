@@ -0,0 +1,193 @@
+//! HTTP transports used by [`Telegram`] and `AsyncTelegram` to reach the Bot
+//! API.
+//!
+//! [`Telegram`]: crate::Telegram
+
+#[cfg(any(target_os = "wasi", test))]
+mod body;
+#[cfg(any(feature = "native", feature = "async"))]
+mod native;
+mod record;
+#[cfg(target_os = "wasi")]
+mod wasi;
+
+use std::{io::Write, sync::Arc};
+
+use anyhow::Result;
+#[cfg(feature = "async")]
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "wasi")]
+pub(crate) use self::body::BodyWriter;
+pub use self::record::{Exchange, RecordingTransport, ReplayTransport};
+#[cfg(target_os = "wasi")]
+pub use self::wasi::HttpReqTransport;
+
+/// An HTTP request to the Bot API.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpRequest {
+    /// HTTP method, e.g. `POST`.
+    pub method: String,
+
+    /// Full url, including the bot token.
+    pub url: String,
+
+    pub headers: Vec<(String, String)>,
+
+    pub body: Vec<u8>,
+}
+
+/// An HTTP response from the Bot API.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpResponse {
+    /// HTTP status code.
+    pub status: u16,
+
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Creates a `POST` request with the given `Content-Type`.
+    #[must_use]
+    pub fn post(url: String, content_type: &str, body: Vec<u8>) -> Self {
+        Self {
+            method: String::from("POST"),
+            url,
+            headers: vec![(String::from("Content-Type"), content_type.to_owned())],
+            body,
+        }
+    }
+
+    /// Creates a `GET` request without a body.
+    #[must_use]
+    pub fn get(url: String) -> Self {
+        Self {
+            method: String::from("GET"),
+            url,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+}
+
+impl HttpResponse {
+    #[must_use]
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// A blocking HTTP transport for [`Telegram`].
+///
+/// Implement this to mock the Bot API, go through a proxy, set custom timeouts
+/// or instrument requests without forking the crate.
+///
+/// [`Telegram`]: crate::Telegram
+pub trait Transport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+
+    /// Sends `request` and writes the body of a successful (2xx) response to
+    /// `writer`, returning the response without its body. Other responses are
+    /// returned whole, without writing anything, except that a transport which
+    /// only learns the status after the body may write a long one and return
+    /// its start.
+    ///
+    /// Used to download files, the default gets the whole response with
+    /// [`send`](Transport::send) first, override it to write the body as it
+    /// arrives.
+    fn send_to(&self, request: HttpRequest, writer: &mut dyn Write) -> Result<HttpResponse> {
+        let response = self.send(request)?;
+        if !(200..300).contains(&response.status) {
+            return Ok(response);
+        }
+
+        writer.write_all(&response.body)?;
+        Ok(HttpResponse::new(response.status, Vec::new()))
+    }
+}
+
+impl<T> Transport for Arc<T>
+where
+    T: Transport + ?Sized,
+{
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        (**self).send(request)
+    }
+
+    fn send_to(&self, request: HttpRequest, writer: &mut dyn Write) -> Result<HttpResponse> {
+        (**self).send_to(request, writer)
+    }
+}
+
+impl<T> Transport for Box<T>
+where
+    T: Transport + ?Sized,
+{
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        (**self).send(request)
+    }
+
+    fn send_to(&self, request: HttpRequest, writer: &mut dyn Write) -> Result<HttpResponse> {
+        (**self).send_to(request, writer)
+    }
+}
+
+/// A non-blocking HTTP transport for `AsyncTelegram`.
+///
+/// Only available with the `async` feature.
+#[cfg(feature = "async")]
+pub trait AsyncTransport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>>;
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncTransport for Arc<T>
+where
+    T: AsyncTransport + ?Sized,
+{
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        (**self).send(request)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncTransport for Box<T>
+where
+    T: AsyncTransport + ?Sized,
+{
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        (**self).send(request)
+    }
+}
+
+/// Returns the transport used by [`Telegram::new`](crate::Telegram::new).
+#[cfg(target_os = "wasi")]
+pub(crate) fn default_transport() -> Box<dyn Transport> {
+    Box::new(HttpReqTransport::default())
+}
+
+/// Returns the transport used by [`Telegram::new`](crate::Telegram::new).
+#[cfg(all(not(target_os = "wasi"), feature = "native"))]
+pub(crate) fn default_transport() -> Box<dyn Transport> {
+    Box::new(reqwest::blocking::Client::new())
+}
+
+/// Returns the transport used by [`Telegram::new`](crate::Telegram::new).
+#[cfg(all(not(target_os = "wasi"), not(feature = "native")))]
+pub(crate) fn default_transport() -> Box<dyn Transport> {
+    struct Unsupported;
+
+    impl Transport for Unsupported {
+        fn send(&self, _: HttpRequest) -> Result<HttpResponse> {
+            Err(anyhow::anyhow!(
+                "no default transport on this target, enable the `native` feature or set one with `Telegram::with_transport`"
+            ))
+        }
+    }
+
+    Box::new(Unsupported)
+}
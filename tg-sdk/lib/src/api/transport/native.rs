@@ -0,0 +1,69 @@
+#[cfg(feature = "native")]
+use std::io::Write;
+
+use anyhow::Result;
+#[cfg(feature = "async")]
+use futures::future::BoxFuture;
+
+#[cfg(feature = "async")]
+use super::AsyncTransport;
+#[cfg(feature = "native")]
+use super::Transport;
+use super::{HttpRequest, HttpResponse};
+
+/// Sends requests with a blocking [`reqwest`] client, configure the client to
+/// set timeouts or proxies.
+///
+/// Note that a blocking client can't be created or dropped inside of an async
+/// runtime, use `AsyncTelegram` there instead.
+#[cfg(feature = "native")]
+impl Transport for reqwest::blocking::Client {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let response = send_blocking(self, request)?;
+
+        let status = response.status().as_u16();
+        Ok(HttpResponse::new(status, response.bytes()?))
+    }
+
+    /// Writes the body to `writer` as it arrives.
+    fn send_to(&self, request: HttpRequest, writer: &mut dyn Write) -> Result<HttpResponse> {
+        let mut response = send_blocking(self, request)?;
+
+        let status = response.status().as_u16();
+        if !response.status().is_success() {
+            return Ok(HttpResponse::new(status, response.bytes()?));
+        }
+        response.copy_to(writer)?;
+        Ok(HttpResponse::new(status, Vec::new()))
+    }
+}
+
+#[cfg(feature = "native")]
+fn send_blocking(
+    client: &reqwest::blocking::Client,
+    request: HttpRequest,
+) -> Result<reqwest::blocking::Response> {
+    let mut builder = client.request(request.method.parse()?, request.url);
+    for (key, value) in request.headers {
+        builder = builder.header(key, value);
+    }
+    Ok(builder.body(request.body).send()?)
+}
+
+/// Sends requests with an async [`reqwest`] client, configure the client to set
+/// timeouts or proxies.
+#[cfg(feature = "async")]
+impl AsyncTransport for reqwest::Client {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            let mut builder = self.request(request.method.parse()?, request.url);
+            for (key, value) in request.headers {
+                builder = builder.header(key, value);
+            }
+            let response = builder.body(request.body).send().await?;
+
+            let status = response.status().as_u16();
+            Ok(HttpResponse::new(status, response.bytes().await?))
+        })
+    }
+}
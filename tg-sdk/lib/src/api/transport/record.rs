@@ -0,0 +1,214 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use anyhow::{anyhow, Result};
+#[cfg(feature = "async")]
+use futures::future::{self, BoxFuture};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "async")]
+use super::AsyncTransport;
+use super::{HttpRequest, HttpResponse, Transport};
+
+/// A request together with the response it got.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub request: HttpRequest,
+    pub response: HttpResponse,
+}
+
+/// A transport that forwards requests to another transport and records every
+/// exchange, e.g. to save them for a [`ReplayTransport`] later.
+pub struct RecordingTransport<T> {
+    inner: T,
+    exchanges: Mutex<Vec<Exchange>>,
+}
+
+impl<T> RecordingTransport<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            exchanges: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the exchanges recorded so far, oldest first.
+    pub fn exchanges(&self) -> Vec<Exchange> {
+        self.exchanges.lock().unwrap().clone()
+    }
+
+    fn record(&self, request: HttpRequest, response: &HttpResponse) {
+        self.exchanges.lock().unwrap().push(Exchange {
+            request,
+            response: response.clone(),
+        });
+    }
+}
+
+impl<T> Transport for RecordingTransport<T>
+where
+    T: Transport,
+{
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let response = self.inner.send(request.clone())?;
+        self.record(request, &response);
+
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncTransport for RecordingTransport<T>
+where
+    T: AsyncTransport,
+{
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            let response = self.inner.send(request.clone()).await?;
+            self.record(request, &response);
+
+            Ok(response)
+        })
+    }
+}
+
+/// A transport that answers requests with canned responses, in order, without
+/// touching the network.
+///
+/// The requests it received are kept and can be inspected with
+/// [`ReplayTransport::requests`].
+#[derive(Default)]
+pub struct ReplayTransport {
+    responses: Mutex<VecDeque<HttpResponse>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl ReplayTransport {
+    pub fn new<I>(responses: I) -> Self
+    where
+        I: IntoIterator<Item = HttpResponse>,
+    {
+        Self {
+            responses: Mutex::new(responses.into_iter().collect()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Replays the responses of exchanges recorded by a
+    /// [`RecordingTransport`].
+    pub fn from_exchanges<I>(exchanges: I) -> Self
+    where
+        I: IntoIterator<Item = Exchange>,
+    {
+        Self::new(exchanges.into_iter().map(|e| e.response))
+    }
+
+    /// Queues a successful Bot API response with the given `result`.
+    pub fn push_result(&self, result: serde_json::Value) {
+        let body = serde_json::json!({ "ok": true, "result": result });
+        self.push(HttpResponse::new(200, body.to_string()));
+    }
+
+    /// Queues a failed Bot API response.
+    pub fn push_error(&self, error_code: u16, description: &str) {
+        let body = serde_json::json!({
+            "ok": false,
+            "error_code": error_code,
+            "description": description,
+        });
+        self.push(HttpResponse::new(error_code, body.to_string()));
+    }
+
+    pub fn push(&self, response: HttpResponse) {
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    /// Returns the requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns the Bot API method and JSON body of the requests received so
+    /// far, with a `null` body for requests that aren't JSON.
+    #[cfg(test)]
+    pub(crate) fn calls(&self) -> Vec<(String, serde_json::Value)> {
+        let requests = self.requests.lock().unwrap();
+        let call = |request: &HttpRequest| {
+            let method = request.url.rsplit('/').next().unwrap_or_default();
+            let body = serde_json::from_slice(&request.body).unwrap_or_default();
+            (method.to_owned(), body)
+        };
+        requests.iter().map(call).collect()
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let url = request.url.clone();
+        self.requests.lock().unwrap().push(request);
+
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow!("no response left to replay for {url}"))
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for ReplayTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(future::ready(Transport::send(self, request)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{ChatId, HttpResponse, RecordingTransport, ReplayTransport, Telegram};
+
+    #[test]
+    fn replay_in_order() {
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push_result(serde_json::json!(true));
+        replay.push_error(400, "Bad Request: chat not found");
+
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        assert!(tele.delete_chat_photo(ChatId(1)).is_ok());
+        assert!(tele.delete_chat_photo(ChatId(2)).is_err());
+        assert!(tele.delete_chat_photo(ChatId(3)).is_err());
+
+        let requests = replay.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(
+            requests[0].url,
+            "https://api.telegram.org/botTOKEN/deleteChatPhoto"
+        );
+        assert_eq!(requests[1].body, br#"{"chat_id":2}"#);
+    }
+
+    #[test]
+    fn record_then_replay() {
+        let source = ReplayTransport::new([HttpResponse::new(
+            200,
+            r#"{"ok":true,"result":"https://t.me/+link"}"#,
+        )]);
+        let recording = Arc::new(RecordingTransport::new(source));
+
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(recording.clone());
+        let link = tele.export_chat_invite_link(ChatId(1)).unwrap();
+
+        let exchanges = recording.exchanges();
+        assert_eq!(exchanges.len(), 1);
+        assert_eq!(exchanges[0].request.body, br#"{"chat_id":1}"#);
+
+        let json = serde_json::to_string(&exchanges).unwrap();
+        let replay =
+            ReplayTransport::from_exchanges(serde_json::from_str::<Vec<_>>(&json).unwrap());
+
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay);
+        assert_eq!(tele.export_chat_invite_link(ChatId(1)).unwrap(), link);
+    }
+}
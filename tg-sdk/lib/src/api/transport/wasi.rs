@@ -0,0 +1,68 @@
+use std::{io::Write, time::Duration};
+
+use anyhow::{anyhow, Result};
+use http_req::{
+    request::{Method, Request},
+    response::Response,
+    uri::Uri,
+};
+
+use super::{BodyWriter, HttpRequest, HttpResponse, Transport};
+
+/// The default transport on wasi, built on `http_req`.
+#[derive(Clone, Debug, Default)]
+pub struct HttpReqTransport {
+    timeout: Option<Duration>,
+}
+
+impl HttpReqTransport {
+    /// Sets the timeout of a whole request.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends `request`, writing the response body to `writer`.
+    fn send_with<W>(&self, request: HttpRequest, writer: &mut W) -> Result<Response>
+    where
+        W: Write,
+    {
+        let uri = Uri::try_from(request.url.as_str())?;
+        let method = match request.method.as_str() {
+            "GET" => Method::GET,
+            "POST" => Method::POST,
+            method => return Err(anyhow!("unsupported http method: {method}")),
+        };
+
+        let mut builder = Request::new(&uri);
+        builder
+            .method(method)
+            .header("Content-Length", &request.body.len())
+            .body(&request.body)
+            .timeout(self.timeout);
+        for (key, value) in &request.headers {
+            builder.header(key, value);
+        }
+
+        Ok(builder.send(writer)?)
+    }
+}
+
+impl Transport for HttpReqTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut writer = Vec::new();
+        let response = self.send_with(request, &mut writer)?;
+
+        Ok(HttpResponse::new(response.status_code().into(), writer))
+    }
+
+    /// Writes the body to `writer` as `http_req` reads it. `http_req` only
+    /// returns the status afterwards, so error responses longer than a few
+    /// KiB are written too.
+    fn send_to(&self, request: HttpRequest, writer: &mut dyn Write) -> Result<HttpResponse> {
+        let mut body = BodyWriter::new(writer);
+        let response = self.send_with(request, &mut body)?;
+        body.finish(response.status_code().into())
+    }
+}
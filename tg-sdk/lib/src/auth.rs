@@ -0,0 +1,127 @@
+//! Verification of user data Telegram signs with the bot token, so that it
+//! can be trusted when it comes from a browser.
+//!
+//! See [`LoginVerifier`] and [`WebAppVerifier`].
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub use login::*;
+pub use web_app::*;
+
+mod login;
+mod web_app;
+
+/// How far in the future `auth_date` can be, from a clock ahead of ours.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// Why signed user data can't be trusted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthError {
+    /// A field is missing, e.g. `"hash"`.
+    MissingField(&'static str),
+
+    /// A field has a value which can't be parsed, or `auth_date` is further
+    /// in the future than clocks drift apart.
+    InvalidField(&'static str),
+
+    /// The hash doesn't match the data, which wasn't signed with the token of
+    /// the bot or was changed since.
+    InvalidHash,
+
+    /// `auth_date` is older than the max age.
+    Expired,
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "missing field `{field}`"),
+            Self::InvalidField(field) => write!(f, "invalid field `{field}`"),
+            Self::InvalidHash => f.write_str("hash doesn't match the data"),
+            Self::Expired => f.write_str("authorization data is too old"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Collects the fields of a query string, or pairs already parsed from one.
+fn collect_fields<I, K, V>(fields: I) -> BTreeMap<String, String>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+{
+    fields
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into()))
+        .collect()
+}
+
+/// Checks, in constant time, that the `hash` field is the hex HMAC-SHA-256
+/// of the other fields sorted by name, as `name=value` lines, with `secret`.
+fn check_hash(secret: &[u8], fields: &BTreeMap<String, String>) -> Result<(), AuthError> {
+    let hash = fields.get("hash").ok_or(AuthError::MissingField("hash"))?;
+    let hash = decode_hex(hash).ok_or(AuthError::InvalidHash)?;
+
+    let data_check_string = fields
+        .iter()
+        .filter(|(name, _)| *name != "hash")
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut mac = hmac_sha256(secret);
+    mac.update(data_check_string.as_bytes());
+    mac.verify_slice(&hash).map_err(|_| AuthError::InvalidHash)
+}
+
+/// Parses the `auth_date` field, and checks it's at most `max_age` before
+/// `now` and at most [`MAX_CLOCK_SKEW`] after it.
+fn check_auth_date(
+    fields: &BTreeMap<String, String>,
+    max_age: Duration,
+    now: SystemTime,
+) -> Result<DateTime<Utc>, AuthError> {
+    let auth_date = fields
+        .get("auth_date")
+        .ok_or(AuthError::MissingField("auth_date"))?;
+    let auth_date = auth_date
+        .parse()
+        .ok()
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        .ok_or(AuthError::InvalidField("auth_date"))?;
+
+    let now = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let date = auth_date.timestamp().max(0) as u64;
+    if date > now.saturating_add(MAX_CLOCK_SKEW.as_secs()) {
+        return Err(AuthError::InvalidField("auth_date"));
+    }
+    match now.saturating_sub(date) > max_age.as_secs() {
+        true => Err(AuthError::Expired),
+        false => Ok(auth_date),
+    }
+}
+
+fn hmac_sha256(key: &[u8]) -> Hmac<Sha256> {
+    Hmac::new_from_slice(key).expect("HMAC takes keys of any size")
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let digit = |b: &u8| char::from(*b).to_digit(16);
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => Some((digit(high)? << 4 | digit(low)?) as u8),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,225 @@
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::{
+    auth::{check_auth_date, check_hash, collect_fields, AuthError},
+    types::{User, UserId},
+};
+
+/// A user authorized with the [Telegram Login Widget] or a [`LoginUrl`]
+/// button, verified by a [`LoginVerifier`].
+///
+/// [Telegram Login Widget]: https://core.telegram.org/widgets/login
+/// [`LoginUrl`]: crate::types::LoginUrl
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LoginData {
+    pub id: UserId,
+    pub first_name: String,
+    pub last_name: Option<String>,
+    pub username: Option<String>,
+    pub photo_url: Option<Url>,
+
+    /// When the user authorized.
+    pub auth_date: DateTime<Utc>,
+}
+
+impl From<LoginData> for User {
+    fn from(data: LoginData) -> Self {
+        Self {
+            id: data.id,
+            is_bot: false,
+            first_name: data.first_name,
+            last_name: data.last_name,
+            username: data.username,
+            language_code: None,
+            is_premium: false,
+            added_to_attachment_menu: false,
+        }
+    }
+}
+
+/// Checks that the authorization data a user's browser passes on, as the
+/// query string of the [Login Widget] redirect or a [`LoginUrl`], was signed
+/// by Telegram for this bot.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use tg_flows::{LoginVerifier, User};
+///
+/// # fn run(query: &str) -> Result<(), tg_flows::AuthError> {
+/// let verifier = LoginVerifier::new(&std::env::var("TOKEN").unwrap())
+///     .max_age(Duration::from_secs(60 * 60));
+///
+/// // e.g. "id=42&first_name=Ann&auth_date=1700000000&hash=..."
+/// let user: User = verifier.verify_query(query)?.into();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [Login Widget]: https://core.telegram.org/widgets/login
+/// [`LoginUrl`]: crate::types::LoginUrl
+#[derive(Clone)]
+pub struct LoginVerifier {
+    secret: [u8; 32],
+    max_age: Duration,
+}
+
+impl LoginVerifier {
+    /// Creates a verifier for data signed for the bot with `token`, which
+    /// accepts data at most a day old.
+    #[must_use]
+    pub fn new(token: &str) -> Self {
+        Self {
+            secret: Sha256::digest(token).into(),
+            max_age: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+
+    /// Changes how old `auth_date` can be, so that leaked links stop working.
+    #[must_use]
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Verifies a query string, with or without the leading `?`.
+    pub fn verify_query(&self, query: &str) -> Result<LoginData, AuthError> {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        self.verify(url::form_urlencoded::parse(query.as_bytes()))
+    }
+
+    /// Verifies fields already parsed from a query string, or a form.
+    pub fn verify<I, K, V>(&self, fields: I) -> Result<LoginData, AuthError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.verify_at(fields, SystemTime::now())
+    }
+
+    /// Verifies fields as [`verify`] does, as if it were `now`.
+    ///
+    /// [`verify`]: LoginVerifier::verify
+    pub fn verify_at<I, K, V>(&self, fields: I, now: SystemTime) -> Result<LoginData, AuthError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut fields = collect_fields(fields);
+        check_hash(&self.secret, &fields)?;
+        let auth_date = check_auth_date(&fields, self.max_age, now)?;
+
+        let id = fields.get("id").ok_or(AuthError::MissingField("id"))?;
+        let id = id.parse().map_err(|_| AuthError::InvalidField("id"))?;
+        let photo_url = match fields.get("photo_url") {
+            Some(url) => Some(
+                url.parse()
+                    .map_err(|_| AuthError::InvalidField("photo_url"))?,
+            ),
+            None => None,
+        };
+
+        Ok(LoginData {
+            id: UserId(id),
+            first_name: fields
+                .remove("first_name")
+                .ok_or(AuthError::MissingField("first_name"))?,
+            last_name: fields.remove("last_name"),
+            username: fields.remove("username"),
+            photo_url,
+            auth_date,
+        })
+    }
+}
+
+impl std::fmt::Debug for LoginVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoginVerifier")
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use crate::{
+        auth::{AuthError, LoginVerifier},
+        types::{User, UserId},
+    };
+
+    const TOKEN: &str = "123456:ABC-DEF1234ghIkl-zyx57W2v1u123ew11";
+    const QUERY: &str = "id=42&first_name=Ann&last_name=Lee&username=ann&photo_url=https%3A%2F%2Ft.me%2Fi%2Fuserpic%2F320%2Fann.jpg&auth_date=1700000000&hash=2e7ed0ac808bb92b6cf0ae46b8cb1a7170940c1c172a9d05301aefd4315350fc";
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn verify(query: &str, now: SystemTime) -> Result<User, AuthError> {
+        let fields = url::form_urlencoded::parse(query.as_bytes());
+        LoginVerifier::new(TOKEN)
+            .verify_at(fields, now)
+            .map(User::from)
+    }
+
+    #[test]
+    fn verifies() {
+        let user = verify(QUERY, at(1_700_000_100)).unwrap();
+        assert_eq!(user.id, UserId(42));
+        assert_eq!(user.full_name(), "Ann Lee");
+        assert_eq!(user.username.as_deref(), Some("ann"));
+
+        let verifier = LoginVerifier::new(TOKEN).max_age(Duration::MAX);
+        let data = verifier.verify_query(&format!("?{QUERY}")).unwrap();
+        assert_eq!(
+            data.photo_url.unwrap().as_str(),
+            "https://t.me/i/userpic/320/ann.jpg"
+        );
+        assert_eq!(data.auth_date.timestamp(), 1_700_000_000);
+
+        // signed long ago for the real clock
+        let verifier = LoginVerifier::new(TOKEN);
+        assert_eq!(verifier.verify_query(QUERY), Err(AuthError::Expired));
+    }
+
+    #[test]
+    fn rejects() {
+        assert_eq!(
+            verify(QUERY, at(1_700_000_000 + 86_401)),
+            Err(AuthError::Expired)
+        );
+        assert_eq!(
+            verify(&QUERY.replace("id=42", "id=43"), at(1_700_000_100)),
+            Err(AuthError::InvalidHash)
+        );
+        assert_eq!(
+            verify(&QUERY.replace("hash=2e", "hash=2f"), at(1_700_000_100)),
+            Err(AuthError::InvalidHash)
+        );
+        assert_eq!(
+            verify("id=42&auth_date=1700000000", at(1_700_000_100)),
+            Err(AuthError::MissingField("hash"))
+        );
+
+        // from a clock ahead of ours, but not too far
+        assert!(verify(QUERY, at(1_700_000_000 - 300)).is_ok());
+        assert_eq!(
+            verify(QUERY, at(1_700_000_000 - 301)),
+            Err(AuthError::InvalidField("auth_date"))
+        );
+
+        let verifier = LoginVerifier::new("654321:other");
+        let fields = url::form_urlencoded::parse(QUERY.as_bytes());
+        assert_eq!(
+            verifier.verify_at(fields, at(1_700_000_100)),
+            Err(AuthError::InvalidHash)
+        );
+    }
+}
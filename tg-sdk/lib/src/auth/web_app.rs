@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Utc};
+use hmac::Mac;
+use serde::{de::DeserializeOwned, Deserialize};
+use url::Url;
+
+use crate::{
+    auth::{check_auth_date, check_hash, collect_fields, hmac_sha256, AuthError},
+    types::{ChatId, ChatType, User, UserId},
+};
+
+/// The launch data of a [Web App], verified by a [`WebAppVerifier`].
+///
+/// [Web App]: https://core.telegram.org/bots/webapps
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InitData {
+    /// Identifies the Web App session, to send a message on behalf of the
+    /// user with [`Telegram::answer_web_app_query`].
+    ///
+    /// [`Telegram::answer_web_app_query`]: crate::Telegram::answer_web_app_query
+    pub query_id: Option<String>,
+
+    /// The user who opened the Web App.
+    pub user: Option<User>,
+
+    /// The other user in the private chat the Web App was opened from, from
+    /// the attachment menu.
+    pub receiver: Option<User>,
+
+    /// The group or channel the Web App was opened from, from the attachment
+    /// menu.
+    pub chat: Option<WebAppChat>,
+
+    /// The type of the chat the Web App was opened from, from a direct link
+    /// or the attachment menu.
+    pub chat_type: Option<ChatType>,
+
+    /// Identifies the chat the Web App was opened from, from a direct link
+    /// or the attachment menu.
+    pub chat_instance: Option<String>,
+
+    /// The `startapp` parameter of the link the Web App was opened with, see
+    /// [`DeepLink::StartApp`] and [`decode_start_payload`].
+    ///
+    /// [`DeepLink::StartApp`]: crate::types::DeepLink::StartApp
+    /// [`decode_start_payload`]: crate::types::decode_start_payload
+    pub start_param: Option<String>,
+
+    /// How long after opening the Web App a message can be sent with
+    /// [`Telegram::answer_web_app_query`].
+    ///
+    /// [`Telegram::answer_web_app_query`]: crate::Telegram::answer_web_app_query
+    pub can_send_after: Option<Duration>,
+
+    /// When the Web App was opened.
+    pub auth_date: DateTime<Utc>,
+}
+
+/// A group, supergroup or channel a Web App was opened from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct WebAppChat {
+    pub id: ChatId,
+
+    #[serde(rename = "type")]
+    pub kind: ChatType,
+
+    pub title: String,
+    pub username: Option<String>,
+    pub photo_url: Option<Url>,
+}
+
+/// A [`User`] as Web Apps get it, with `is_bot` only for bots.
+#[derive(Deserialize)]
+struct WebAppUser {
+    id: UserId,
+    #[serde(default)]
+    is_bot: bool,
+    first_name: String,
+    last_name: Option<String>,
+    username: Option<String>,
+    language_code: Option<String>,
+    #[serde(default)]
+    is_premium: bool,
+    #[serde(default)]
+    added_to_attachment_menu: bool,
+}
+
+impl From<WebAppUser> for User {
+    fn from(user: WebAppUser) -> Self {
+        Self {
+            id: user.id,
+            is_bot: user.is_bot,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            username: user.username,
+            language_code: user.language_code,
+            is_premium: user.is_premium,
+            added_to_attachment_menu: user.added_to_attachment_menu,
+        }
+    }
+}
+
+/// Checks that the `initData` a [Web App] sends to its backend, from
+/// `window.Telegram.WebApp.initData`, was signed by Telegram for this bot.
+///
+/// ```no_run
+/// use tg_flows::{InlineQueryResultArticle, InputMessageContent, InputMessageContentText,
+///     Telegram, WebAppVerifier};
+///
+/// # fn run(init_data: &str) -> anyhow::Result<()> {
+/// let token = std::env::var("TOKEN")?;
+/// let init_data = WebAppVerifier::new(&token).verify(init_data)?;
+///
+/// if let Some(query_id) = init_data.query_id {
+///     let content = InputMessageContent::Text(InputMessageContentText::new("Ordered!"));
+///     let result = InlineQueryResultArticle::new("order", "Order", content);
+///     Telegram::new(token).answer_web_app_query(query_id, result)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [Web App]: https://core.telegram.org/bots/webapps
+#[derive(Clone)]
+pub struct WebAppVerifier {
+    secret: [u8; 32],
+    max_age: Duration,
+}
+
+impl WebAppVerifier {
+    /// Creates a verifier for data signed for the bot with `token`, which
+    /// accepts data at most a day old.
+    #[must_use]
+    pub fn new(token: &str) -> Self {
+        let mut mac = hmac_sha256(b"WebAppData");
+        mac.update(token.as_bytes());
+
+        Self {
+            secret: mac.finalize().into_bytes().into(),
+            max_age: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+
+    /// Changes how old `auth_date` can be, so that leaked data stops working.
+    #[must_use]
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Verifies `initData`, the query string the Web App got it as.
+    pub fn verify(&self, init_data: &str) -> Result<InitData, AuthError> {
+        self.verify_at(init_data, SystemTime::now())
+    }
+
+    /// Verifies `initData` as [`verify`] does, as if it were `now`.
+    ///
+    /// [`verify`]: WebAppVerifier::verify
+    pub fn verify_at(&self, init_data: &str, now: SystemTime) -> Result<InitData, AuthError> {
+        let mut fields = collect_fields(url::form_urlencoded::parse(init_data.as_bytes()));
+        check_hash(&self.secret, &fields)?;
+        let auth_date = check_auth_date(&fields, self.max_age, now)?;
+
+        let can_send_after = parse_json::<u64>(&fields, "can_send_after")?;
+        Ok(InitData {
+            query_id: fields.remove("query_id"),
+            user: parse_json::<WebAppUser>(&fields, "user")?.map(User::from),
+            receiver: parse_json::<WebAppUser>(&fields, "receiver")?.map(User::from),
+            chat: parse_json(&fields, "chat")?,
+            chat_type: parse_json(&fields, "chat_type")?,
+            chat_instance: fields.remove("chat_instance"),
+            start_param: fields.remove("start_param"),
+            can_send_after: can_send_after.map(Duration::from_secs),
+            auth_date,
+        })
+    }
+}
+
+impl std::fmt::Debug for WebAppVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebAppVerifier")
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Parses an optional field holding JSON, or an enum as a bare string.
+fn parse_json<T>(
+    fields: &BTreeMap<String, String>,
+    name: &'static str,
+) -> Result<Option<T>, AuthError>
+where
+    T: DeserializeOwned,
+{
+    let Some(value) = fields.get(name) else {
+        return Ok(None);
+    };
+    serde_json::from_str(value)
+        .or_else(|_| serde_json::from_value(serde_json::Value::String(value.clone())))
+        .map(Some)
+        .map_err(|_| AuthError::InvalidField(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::{
+        auth::{AuthError, WebAppVerifier},
+        types::{ChatId, ChatType, UserId},
+    };
+
+    const TOKEN: &str = "123456:ABC-DEF1234ghIkl-zyx57W2v1u123ew11";
+    const INIT_DATA: &str = "query_id=AAHdF6IQAAAAAN0XohDhrOrc&user=%7B%22id%22%3A42%2C%22first_name%22%3A%22Ann%22%2C%22username%22%3A%22ann%22%2C%22language_code%22%3A%22en%22%2C%22allows_write_to_pm%22%3Atrue%7D&chat=%7B%22id%22%3A-1001234%2C%22type%22%3A%22supergroup%22%2C%22title%22%3A%22Shop%22%7D&chat_type=supergroup&chat_instance=-8765&start_param=cmVmLTQy&can_send_after=5&auth_date=1700000000&hash=969273d728ef2bb68ed654db3699ff0f7ac6a2fea4c1f6e3f2eafd48bf269b64";
+
+    #[test]
+    fn verifies() {
+        let verifier = WebAppVerifier::new(TOKEN);
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_100);
+        let data = verifier.verify_at(INIT_DATA, now).unwrap();
+
+        assert_eq!(data.query_id.as_deref(), Some("AAHdF6IQAAAAAN0XohDhrOrc"));
+        let user = data.user.unwrap();
+        assert_eq!((user.id, user.first_name.as_str()), (UserId(42), "Ann"));
+        assert_eq!(data.receiver, None);
+        let chat = data.chat.unwrap();
+        assert_eq!(
+            (chat.id, chat.kind, chat.title.as_str()),
+            (ChatId(-1001234), ChatType::Supergroup, "Shop")
+        );
+        assert_eq!(data.chat_type, Some(ChatType::Supergroup));
+        assert_eq!(data.chat_instance.as_deref(), Some("-8765"));
+        assert_eq!(data.start_param.as_deref(), Some("cmVmLTQy"));
+        assert_eq!(data.can_send_after, Some(Duration::from_secs(5)));
+        assert_eq!(data.auth_date.timestamp(), 1_700_000_000);
+
+        let late = now + Duration::from_secs(24 * 60 * 60);
+        assert_eq!(verifier.verify_at(INIT_DATA, late), Err(AuthError::Expired));
+        let forged = INIT_DATA.replace("%22id%22%3A42", "%22id%22%3A43");
+        assert_eq!(
+            verifier.verify_at(&forged, now),
+            Err(AuthError::InvalidHash)
+        );
+        let other = WebAppVerifier::new("654321:other");
+        assert_eq!(other.verify_at(INIT_DATA, now), Err(AuthError::InvalidHash));
+    }
+}
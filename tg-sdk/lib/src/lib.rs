@@ -1,15 +1,25 @@
 mod api;
+mod auth;
+#[cfg(feature = "passport")]
+mod passport;
+mod text;
 mod types;
 mod util;
 
 pub use api::*;
+pub use auth::*;
+#[cfg(feature = "passport")]
+pub use passport::*;
+pub use text::*;
 pub use types::*;
 
 pub use tg_flows_macros::*;
 
+#[cfg(target_os = "wasi")]
 use http_req::request;
 use lazy_static::lazy_static;
 
+#[cfg(target_os = "wasi")]
 use flowsnet_platform_sdk::write_error_log;
 
 lazy_static! {
@@ -18,6 +28,7 @@ lazy_static! {
     );
 }
 
+#[cfg(target_os = "wasi")]
 extern "C" {
     // Return the user id of the flows platform
     fn get_flows_user(p: *mut u8) -> i32;
@@ -29,6 +40,7 @@ extern "C" {
     fn set_error_code(code: i16);
 }
 
+#[cfg(target_os = "wasi")]
 unsafe fn _get_flows_user() -> String {
     let mut flows_user = Vec::<u8>::with_capacity(100);
     let c = get_flows_user(flows_user.as_mut_ptr());
@@ -36,6 +48,7 @@ unsafe fn _get_flows_user() -> String {
     String::from_utf8(flows_user).unwrap()
 }
 
+#[cfg(target_os = "wasi")]
 unsafe fn _get_flow_id() -> String {
     let mut flow_id = Vec::<u8>::with_capacity(100);
     let c = get_flow_id(flow_id.as_mut_ptr());
@@ -51,6 +64,7 @@ unsafe fn _get_flow_id() -> String {
 /// Before creating the listener, this function will revoke previous
 /// registered listener of current flow so you don't need to do it manually.
 ///
+#[cfg(target_os = "wasi")]
 pub async fn listen_to_update<T>(token: T)
 where
     T: ToString,
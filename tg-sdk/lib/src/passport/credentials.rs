@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::EncryptedPassportElementKind;
+
+/// Decrypted [`EncryptedCredentials`].
+///
+/// [The official docs](https://core.telegram.org/passport#credentials).
+///
+/// [`EncryptedCredentials`]: crate::EncryptedCredentials
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct PassportCredentials {
+    /// Credentials for the encrypted data.
+    pub secure_data: SecureData,
+
+    /// The nonce the bot passed when requesting the data, it must be checked
+    /// to avoid replay attacks.
+    #[serde(alias = "payload")]
+    pub nonce: String,
+}
+
+/// Credentials for each type of shared data.
+///
+/// [The official docs](https://core.telegram.org/passport#securedata).
+#[serde_with_macros::skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct SecureData {
+    pub personal_details: Option<SecureValue>,
+    pub passport: Option<SecureValue>,
+    pub internal_passport: Option<SecureValue>,
+    pub driver_license: Option<SecureValue>,
+    pub identity_card: Option<SecureValue>,
+    pub address: Option<SecureValue>,
+    pub utility_bill: Option<SecureValue>,
+    pub bank_statement: Option<SecureValue>,
+    pub rental_agreement: Option<SecureValue>,
+    pub passport_registration: Option<SecureValue>,
+    pub temporary_registration: Option<SecureValue>,
+}
+
+impl SecureData {
+    /// Returns the credentials for elements of `kind`, `None` for phone numbers
+    /// and emails since they aren't encrypted.
+    #[must_use]
+    pub fn get(&self, kind: &EncryptedPassportElementKind) -> Option<&SecureValue> {
+        use EncryptedPassportElementKind::*;
+
+        match kind {
+            PersonalDetails(_) => self.personal_details.as_ref(),
+            Passport(_) => self.passport.as_ref(),
+            InternalPassport(_) => self.internal_passport.as_ref(),
+            DriverLicense(_) => self.driver_license.as_ref(),
+            IdentityCard(_) => self.identity_card.as_ref(),
+            Address(_) => self.address.as_ref(),
+            UtilityBill(_) => self.utility_bill.as_ref(),
+            BankStatement(_) => self.bank_statement.as_ref(),
+            RentalAgreement(_) => self.rental_agreement.as_ref(),
+            PassportRegistration(_) => self.passport_registration.as_ref(),
+            TemporaryRegistration(_) => self.temporary_registration.as_ref(),
+            PhoneNumber(_) | Email(_) => None,
+        }
+    }
+}
+
+/// Credentials for the parts of a single passport element.
+///
+/// [The official docs](https://core.telegram.org/passport#securevalue).
+#[serde_with_macros::skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct SecureValue {
+    pub data: Option<DataCredentials>,
+    pub front_side: Option<FileCredentials>,
+    pub reverse_side: Option<FileCredentials>,
+    pub selfie: Option<FileCredentials>,
+    pub translation: Option<Vec<FileCredentials>>,
+    pub files: Option<Vec<FileCredentials>>,
+}
+
+/// Credentials for the `data` field of a passport element.
+///
+/// [The official docs](https://core.telegram.org/passport#datacredentials).
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct DataCredentials {
+    /// Base64-encoded checksum of the data.
+    pub data_hash: String,
+
+    /// Base64-encoded secret of the data.
+    pub secret: String,
+}
+
+/// Credentials for a [`PassportFile`].
+///
+/// [The official docs](https://core.telegram.org/passport#filecredentials).
+///
+/// [`PassportFile`]: crate::PassportFile
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct FileCredentials {
+    /// Base64-encoded checksum of the file.
+    pub file_hash: String,
+
+    /// Base64-encoded secret of the file.
+    pub secret: String,
+}
@@ -0,0 +1,390 @@
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    CountryCode, FileCredentials, PassportElementError, PassportElementErrorDataField,
+    PassportElementErrorDataFieldType, PassportElementErrorFile, PassportElementErrorFiles,
+    PassportElementErrorFrontSide, PassportElementErrorKind, PassportElementErrorReverseSide,
+    PassportElementErrorSelfie, PassportElementErrorTranslationFile,
+    PassportElementErrorTranslationFiles, PassportElementErrorUnspecifiedType, PassportFile,
+};
+
+/// All the data a user shared, decrypted with [`PassportKey::decrypt`].
+///
+/// [`PassportKey::decrypt`]: crate::PassportKey::decrypt
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecryptedPassportData {
+    pub elements: Vec<DecryptedPassportElement>,
+
+    /// The nonce the bot passed when requesting the data, it must be checked
+    /// to avoid replay attacks.
+    pub nonce: String,
+}
+
+/// A decrypted [`EncryptedPassportElement`].
+///
+/// [`EncryptedPassportElement`]: crate::EncryptedPassportElement
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecryptedPassportElement {
+    /// Base64-encoded hash of the encrypted element.
+    pub hash: String,
+
+    /// Base64-encoded hash of the element's `data`, if it has any.
+    pub data_hash: Option<String>,
+
+    pub kind: DecryptedPassportElementKind,
+
+    /// The type of the encrypted element, for errors about it.
+    pub(super) element_type: PassportElementErrorUnspecifiedType,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum DecryptedPassportElementKind {
+    PersonalDetails(PersonalDetails),
+    Passport(IdDocument),
+    DriverLicense(IdDocument),
+    IdentityCard(IdDocument),
+    InternalPassport(IdDocument),
+    Address(ResidentialAddress),
+    UtilityBill(PassportDocuments),
+    BankStatement(PassportDocuments),
+    RentalAgreement(PassportDocuments),
+    PassportRegistration(PassportDocuments),
+    TemporaryRegistration(PassportDocuments),
+    PhoneNumber(String),
+    Email(String),
+}
+
+/// Personal details of a user.
+///
+/// [The official docs](https://core.telegram.org/passport#personaldetails).
+#[serde_with_macros::skip_serializing_none]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonalDetails {
+    pub first_name: String,
+
+    pub last_name: String,
+
+    #[serde(default)]
+    pub middle_name: Option<String>,
+
+    #[serde(with = "passport_date")]
+    pub birth_date: NaiveDate,
+
+    pub gender: Gender,
+
+    /// Citizenship.
+    pub country_code: CountryCode,
+
+    pub residence_country_code: CountryCode,
+
+    /// First name in the language of the user's country of residence.
+    #[serde(default)]
+    pub first_name_native: Option<String>,
+
+    #[serde(default)]
+    pub last_name_native: Option<String>,
+
+    #[serde(default)]
+    pub middle_name_native: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Gender {
+    Male,
+    Female,
+}
+
+/// A residential address.
+///
+/// [The official docs](https://core.telegram.org/passport#residentialaddress).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResidentialAddress {
+    pub street_line1: String,
+
+    #[serde(default)]
+    pub street_line2: String,
+
+    pub city: String,
+
+    #[serde(default)]
+    pub state: String,
+
+    pub country_code: CountryCode,
+
+    pub post_code: String,
+}
+
+/// Data of an identity document.
+///
+/// [The official docs](https://core.telegram.org/passport#iddocumentdata).
+#[serde_with_macros::skip_serializing_none]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdDocumentData {
+    pub document_no: String,
+
+    #[serde(default, with = "passport_date::optional")]
+    pub expiry_date: Option<NaiveDate>,
+}
+
+/// A passport, driver license, identity card or internal passport.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdDocument {
+    pub data: IdDocumentData,
+
+    pub front_side: Option<EncryptedPassportFile>,
+
+    pub reverse_side: Option<EncryptedPassportFile>,
+
+    pub selfie: Option<EncryptedPassportFile>,
+
+    pub translation: Vec<EncryptedPassportFile>,
+}
+
+/// Scans of a utility bill, bank statement, rental agreement, passport
+/// registration or temporary registration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PassportDocuments {
+    pub files: Vec<EncryptedPassportFile>,
+
+    pub translation: Vec<EncryptedPassportFile>,
+}
+
+/// A [`PassportFile`] with what's needed to decrypt it once downloaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedPassportFile {
+    pub file: PassportFile,
+
+    pub credentials: FileCredentials,
+}
+
+impl EncryptedPassportFile {
+    /// Decrypts the downloaded contents of the file.
+    pub fn decrypt(&self, contents: &[u8]) -> Result<Vec<u8>> {
+        super::decrypt_with(
+            contents,
+            &self.credentials.secret,
+            &self.credentials.file_hash,
+        )
+    }
+}
+
+impl DecryptedPassportElement {
+    /// An issue with the `field_name` field of the element's data, `None` if
+    /// the element has no data.
+    pub fn data_field_error<S1, S2>(
+        &self,
+        field_name: S1,
+        message: S2,
+    ) -> Option<PassportElementError>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        let r#type: PassportElementErrorDataFieldType = self.error_type()?;
+        let kind = PassportElementErrorDataField::new(r#type, field_name, self.data_hash.clone()?);
+        Some(PassportElementError::new(
+            message,
+            PassportElementErrorKind::DataField(kind),
+        ))
+    }
+
+    /// An issue with `file`, one of the element's scans, `None` if `file`
+    /// doesn't belong to the element.
+    pub fn file_error<S>(
+        &self,
+        file: &EncryptedPassportFile,
+        message: S,
+    ) -> Option<PassportElementError>
+    where
+        S: Into<String>,
+    {
+        use DecryptedPassportElementKind as D;
+        use PassportElementErrorKind as K;
+
+        let hash = file.credentials.file_hash.clone();
+        let kind = match &self.kind {
+            D::Passport(document)
+            | D::DriverLicense(document)
+            | D::IdentityCard(document)
+            | D::InternalPassport(document) => {
+                if document.front_side.as_ref() == Some(file) {
+                    K::FrontSide(PassportElementErrorFrontSide::new(self.error_type()?, hash))
+                } else if document.reverse_side.as_ref() == Some(file) {
+                    K::ReverseSide(PassportElementErrorReverseSide::new(
+                        self.error_type()?,
+                        hash,
+                    ))
+                } else if document.selfie.as_ref() == Some(file) {
+                    K::Selfie(PassportElementErrorSelfie::new(self.error_type()?, hash))
+                } else if document.translation.contains(file) {
+                    K::TranslationFile(PassportElementErrorTranslationFile::new(
+                        self.error_type()?,
+                        hash,
+                    ))
+                } else {
+                    return None;
+                }
+            }
+            D::UtilityBill(documents)
+            | D::BankStatement(documents)
+            | D::RentalAgreement(documents)
+            | D::PassportRegistration(documents)
+            | D::TemporaryRegistration(documents) => {
+                if documents.files.contains(file) {
+                    K::File(PassportElementErrorFile::new(self.error_type()?, hash))
+                } else if documents.translation.contains(file) {
+                    K::TranslationFile(PassportElementErrorTranslationFile::new(
+                        self.error_type()?,
+                        hash,
+                    ))
+                } else {
+                    return None;
+                }
+            }
+            _ => return None,
+        };
+
+        Some(PassportElementError::new(message, kind))
+    }
+
+    /// An issue with the list of scans as a whole, `None` if the element has
+    /// no such list.
+    pub fn files_error<S>(&self, message: S) -> Option<PassportElementError>
+    where
+        S: Into<String>,
+    {
+        use DecryptedPassportElementKind as D;
+
+        let files = match &self.kind {
+            D::UtilityBill(documents)
+            | D::BankStatement(documents)
+            | D::RentalAgreement(documents)
+            | D::PassportRegistration(documents)
+            | D::TemporaryRegistration(documents) => &documents.files,
+            _ => return None,
+        };
+
+        let kind = PassportElementErrorFiles::new(self.error_type()?, file_hashes(files));
+        Some(PassportElementError::new(
+            message,
+            PassportElementErrorKind::Files(kind),
+        ))
+    }
+
+    /// An issue with the translation as a whole, `None` if the element can't
+    /// have one.
+    pub fn translation_files_error<S>(&self, message: S) -> Option<PassportElementError>
+    where
+        S: Into<String>,
+    {
+        use DecryptedPassportElementKind as D;
+
+        let translation = match &self.kind {
+            D::Passport(document)
+            | D::DriverLicense(document)
+            | D::IdentityCard(document)
+            | D::InternalPassport(document) => &document.translation,
+            D::UtilityBill(documents)
+            | D::BankStatement(documents)
+            | D::RentalAgreement(documents)
+            | D::PassportRegistration(documents)
+            | D::TemporaryRegistration(documents) => &documents.translation,
+            _ => return None,
+        };
+
+        let kind =
+            PassportElementErrorTranslationFiles::new(self.error_type()?, file_hashes(translation));
+        Some(PassportElementError::new(
+            message,
+            PassportElementErrorKind::TranslationFiles(kind),
+        ))
+    }
+
+    /// An issue with the element as a whole.
+    pub fn unspecified_error<S>(&self, message: S) -> PassportElementError
+    where
+        S: Into<String>,
+    {
+        PassportElementError::unspecified(message, self.element_type, self.hash.clone())
+    }
+
+    /// Converts the element type to the `type` of a specific error, `None` if
+    /// that error doesn't apply to this element.
+    ///
+    /// All the error types are subsets of the element types serialized the
+    /// same way, so this goes through their serialized name.
+    fn error_type<T>(&self) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::to_value(self.element_type)
+            .and_then(serde_json::from_value)
+            .ok()
+    }
+}
+
+fn file_hashes(files: &[EncryptedPassportFile]) -> Vec<String> {
+    files
+        .iter()
+        .map(|file| file.credentials.file_hash.clone())
+        .collect()
+}
+
+/// (De)serializes dates in the `DD.MM.YYYY` format.
+mod passport_date {
+    use super::*;
+
+    const FORMAT: &str = "%d.%m.%Y";
+
+    pub(super) fn serialize<S>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (day, month, year) = (date.day(), date.month(), date.year());
+        serializer.collect_str(&format_args!("{day:02}.{month:02}.{year:04}"))
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let date = String::deserialize(deserializer)?;
+        NaiveDate::parse_from_str(&date, FORMAT).map_err(serde::de::Error::custom)
+    }
+
+    pub(super) mod optional {
+        use super::*;
+
+        pub(in super::super) fn serialize<S>(
+            date: &Option<NaiveDate>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match date {
+                Some(date) => super::serialize(date, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        /// Treats an empty string as no date.
+        pub(in super::super) fn deserialize<'de, D>(
+            deserializer: D,
+        ) -> Result<Option<NaiveDate>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            match Option::<String>::deserialize(deserializer)? {
+                Some(date) if !date.is_empty() => NaiveDate::parse_from_str(&date, FORMAT)
+                    .map(Some)
+                    .map_err(serde::de::Error::custom),
+                _ => Ok(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,578 @@
+//! Decryption of [Telegram Passport] data.
+//!
+//! A [`PassportKey`] decrypts the [`PassportData`] of a message with the bot's
+//! private RSA key, yielding typed personal details, identity documents and
+//! addresses. Files are decrypted separately with
+//! [`EncryptedPassportFile::decrypt`] once downloaded.
+//!
+//! [Telegram Passport]: https://core.telegram.org/passport
+
+mod credentials;
+mod element;
+
+use std::fmt;
+
+use aes::Aes256;
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use cbc::cipher::{block_padding::NoPadding, BlockDecryptMut, KeyIvInit};
+use rsa::{pkcs1::DecodeRsaPrivateKey, pkcs8::DecodePrivateKey, Oaep, RsaPrivateKey};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::{
+    EncryptedCredentials, EncryptedPassportElement, EncryptedPassportElementKind, PassportData,
+    PassportFile,
+};
+
+pub use self::{
+    credentials::{DataCredentials, FileCredentials, PassportCredentials, SecureData, SecureValue},
+    element::{
+        DecryptedPassportData, DecryptedPassportElement, DecryptedPassportElementKind,
+        EncryptedPassportFile, Gender, IdDocument, IdDocumentData, PassportDocuments,
+        PersonalDetails, ResidentialAddress,
+    },
+};
+
+/// Why passport data couldn't be decrypted.
+///
+/// Decryption methods return it wrapped in an [`anyhow::Error`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PassportError {
+    /// The credentials secret isn't encrypted with the bot's key.
+    InvalidSecret,
+
+    /// The decrypted data doesn't match its hash.
+    HashMismatch,
+
+    /// The decrypted data isn't correctly padded.
+    InvalidPadding,
+
+    /// The credentials don't cover an element that needs them.
+    MissingCredentials,
+}
+
+impl fmt::Display for PassportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidSecret => "passport secret can't be decrypted with this key",
+            Self::HashMismatch => "passport data doesn't match its hash",
+            Self::InvalidPadding => "passport data is badly padded",
+            Self::MissingCredentials => "passport element has no credentials",
+        })
+    }
+}
+
+impl std::error::Error for PassportError {}
+
+/// The bot's private key, used to decrypt Telegram Passport data.
+#[derive(Clone, Debug)]
+pub struct PassportKey {
+    key: RsaPrivateKey,
+}
+
+impl PassportKey {
+    /// Reads a PEM encoded PKCS#1 (`BEGIN RSA PRIVATE KEY`) or PKCS#8
+    /// (`BEGIN PRIVATE KEY`) private key.
+    pub fn from_pem(pem: &str) -> Result<Self> {
+        let key = RsaPrivateKey::from_pkcs1_pem(pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs8_pem(pem))
+            .context("invalid passport private key")?;
+
+        Ok(Self { key })
+    }
+
+    /// Decrypts every element of `data`.
+    pub fn decrypt(&self, data: &PassportData) -> Result<DecryptedPassportData> {
+        let credentials = self.decrypt_credentials(&data.credentials)?;
+        let elements = data
+            .data
+            .iter()
+            .map(|element| decrypt_element(element, &credentials.secure_data))
+            .collect::<Result<_>>()?;
+
+        Ok(DecryptedPassportData {
+            elements,
+            nonce: credentials.nonce,
+        })
+    }
+
+    pub fn decrypt_credentials(
+        &self,
+        credentials: &EncryptedCredentials,
+    ) -> Result<PassportCredentials> {
+        let secret = self
+            .key
+            .decrypt(Oaep::new::<sha1::Sha1>(), &decode(&credentials.secret)?)
+            .map_err(|_| PassportError::InvalidSecret)?;
+
+        let data = decrypt(
+            &decode(&credentials.data)?,
+            &secret,
+            &decode(&credentials.hash)?,
+        )?;
+        serde_json::from_slice(&data).context("invalid passport credentials")
+    }
+}
+
+/// Decrypts `element` with the credentials in `secure_data`.
+pub fn decrypt_element(
+    element: &EncryptedPassportElement,
+    secure_data: &SecureData,
+) -> Result<DecryptedPassportElement> {
+    use DecryptedPassportElementKind as D;
+    use EncryptedPassportElementKind as E;
+
+    let value = secure_data.get(&element.kind);
+    let data_hash = value
+        .and_then(|value| value.data.as_ref())
+        .map(|data| data.data_hash.clone());
+
+    let kind = match &element.kind {
+        E::PersonalDetails(e) => D::PersonalDetails(decrypt_data(&e.data, value)?),
+        E::Passport(e) => D::Passport(IdDocument {
+            data: decrypt_data(&e.data, value)?,
+            front_side: Some(file(&e.front_side, value, |v| &v.front_side)?),
+            reverse_side: None,
+            selfie: optional_file(e.selfie.as_ref(), value, |v| &v.selfie)?,
+            translation: files(e.translation.as_deref(), value, |v| &v.translation)?,
+        }),
+        E::DriverLicense(e) => D::DriverLicense(IdDocument {
+            data: decrypt_data(&e.data, value)?,
+            front_side: Some(file(&e.front_side, value, |v| &v.front_side)?),
+            reverse_side: Some(file(&e.reverse_side, value, |v| &v.reverse_side)?),
+            selfie: optional_file(e.selfie.as_ref(), value, |v| &v.selfie)?,
+            translation: files(e.translation.as_deref(), value, |v| &v.translation)?,
+        }),
+        E::IdentityCard(e) => D::IdentityCard(IdDocument {
+            data: decrypt_data(&e.data, value)?,
+            front_side: Some(file(&e.front_side, value, |v| &v.front_side)?),
+            reverse_side: Some(file(&e.reverse_side, value, |v| &v.reverse_side)?),
+            selfie: optional_file(e.selfie.as_ref(), value, |v| &v.selfie)?,
+            translation: files(e.translation.as_deref(), value, |v| &v.translation)?,
+        }),
+        E::InternalPassport(e) => D::InternalPassport(IdDocument {
+            data: decrypt_data(&e.data, value)?,
+            front_side: Some(file(&e.front_side, value, |v| &v.front_side)?),
+            reverse_side: None,
+            selfie: optional_file(e.selfie.as_ref(), value, |v| &v.selfie)?,
+            translation: files(e.translation.as_deref(), value, |v| &v.translation)?,
+        }),
+        E::Address(e) => D::Address(decrypt_data(&e.data, value)?),
+        E::UtilityBill(e) => D::UtilityBill(documents(&e.files, e.translation.as_deref(), value)?),
+        E::BankStatement(e) => {
+            D::BankStatement(documents(&e.files, e.translation.as_deref(), value)?)
+        }
+        E::RentalAgreement(e) => {
+            D::RentalAgreement(documents(&e.files, e.translation.as_deref(), value)?)
+        }
+        E::PassportRegistration(e) => {
+            D::PassportRegistration(documents(&e.files, e.translation.as_deref(), value)?)
+        }
+        E::TemporaryRegistration(e) => {
+            D::TemporaryRegistration(documents(&e.files, e.translation.as_deref(), value)?)
+        }
+        E::PhoneNumber(e) => D::PhoneNumber(e.phone_number.clone()),
+        E::Email(e) => D::Email(e.email.clone()),
+    };
+
+    Ok(DecryptedPassportElement {
+        hash: element.hash.clone(),
+        data_hash,
+        kind,
+        element_type: (&element.kind).into(),
+    })
+}
+
+fn decrypt_data<T>(data: &str, value: Option<&SecureValue>) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let credentials = value
+        .and_then(|value| value.data.as_ref())
+        .ok_or(PassportError::MissingCredentials)?;
+
+    let data = decrypt_with(&decode(data)?, &credentials.secret, &credentials.data_hash)?;
+    serde_json::from_slice(&data).context("invalid passport element data")
+}
+
+fn file<F>(
+    file: &PassportFile,
+    value: Option<&SecureValue>,
+    credentials: F,
+) -> Result<EncryptedPassportFile>
+where
+    F: Fn(&SecureValue) -> &Option<FileCredentials>,
+{
+    let credentials = value
+        .and_then(|value| credentials(value).clone())
+        .ok_or(PassportError::MissingCredentials)?;
+
+    Ok(EncryptedPassportFile {
+        file: file.clone(),
+        credentials,
+    })
+}
+
+fn optional_file<F>(
+    file: Option<&PassportFile>,
+    value: Option<&SecureValue>,
+    credentials: F,
+) -> Result<Option<EncryptedPassportFile>>
+where
+    F: Fn(&SecureValue) -> &Option<FileCredentials>,
+{
+    file.map(|f| self::file(f, value, credentials)).transpose()
+}
+
+fn files<F>(
+    files: Option<&[PassportFile]>,
+    value: Option<&SecureValue>,
+    credentials: F,
+) -> Result<Vec<EncryptedPassportFile>>
+where
+    F: Fn(&SecureValue) -> &Option<Vec<FileCredentials>>,
+{
+    let files = files.unwrap_or_default();
+    let credentials = value
+        .and_then(|value| credentials(value).as_deref())
+        .unwrap_or_default();
+    if files.len() != credentials.len() {
+        return Err(PassportError::MissingCredentials.into());
+    }
+
+    Ok(files
+        .iter()
+        .zip(credentials)
+        .map(|(file, credentials)| EncryptedPassportFile {
+            file: file.clone(),
+            credentials: credentials.clone(),
+        })
+        .collect())
+}
+
+fn documents(
+    files: &[PassportFile],
+    translation: Option<&[PassportFile]>,
+    value: Option<&SecureValue>,
+) -> Result<PassportDocuments> {
+    Ok(PassportDocuments {
+        files: self::files(Some(files), value, |v| &v.files)?,
+        translation: self::files(translation, value, |v| &v.translation)?,
+    })
+}
+
+fn decode(base64: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(base64)
+        .context("invalid base64 in passport data")
+}
+
+/// Decrypts `data` with base64-encoded `secret` and `hash`.
+pub(crate) fn decrypt_with(data: &[u8], secret: &str, hash: &str) -> Result<Vec<u8>> {
+    decrypt(data, &decode(secret)?, &decode(hash)?)
+}
+
+/// Decrypts `data` as described in
+/// <https://core.telegram.org/passport#decrypting-data>.
+fn decrypt(data: &[u8], secret: &[u8], hash: &[u8]) -> Result<Vec<u8>> {
+    let digest = Sha512::new()
+        .chain_update(secret)
+        .chain_update(hash)
+        .finalize();
+    let (key, iv) = (&digest[..32], &digest[32..48]);
+
+    if data.is_empty() || !data.len().is_multiple_of(16) {
+        return Err(PassportError::InvalidPadding.into());
+    }
+    let mut data = data.to_vec();
+    cbc::Decryptor::<Aes256>::new(key.into(), iv.into())
+        .decrypt_padded_mut::<NoPadding>(&mut data)
+        .map_err(|_| PassportError::InvalidPadding)?;
+
+    if Sha256::digest(&data).as_slice() != hash {
+        return Err(PassportError::HashMismatch.into());
+    }
+
+    let padding = data[0] as usize;
+    if padding == 0 || padding > data.len() {
+        return Err(PassportError::InvalidPadding.into());
+    }
+    data.drain(..padding);
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::NaiveDate;
+    use serde::Deserialize;
+
+    use crate::{
+        CountryCode, DecryptedPassportElementKind as D, EncryptedPassportElementKind as E,
+        EncryptedPassportElementPersonalDetails, Gender, PassportData,
+        PassportElementErrorDataField, PassportElementErrorDataFieldType, PassportElementErrorKind,
+        PassportElementErrorSelfie, PassportElementErrorSelfieType,
+        PassportElementErrorUnspecified, PassportElementErrorUnspecifiedType, PassportError,
+        PassportKey, ReplayTransport, Telegram, UserId,
+    };
+
+    /// Data encrypted with a throwaway key as described in the Telegram
+    /// Passport docs.
+    #[derive(Deserialize)]
+    struct Sample {
+        private_key: String,
+        passport_data: PassportData,
+        files: std::collections::HashMap<String, String>,
+    }
+
+    fn sample() -> Sample {
+        serde_json::from_str(include_str!("testdata/sample.json")).unwrap()
+    }
+
+    /// Data encrypted with the openssl CLI by `testdata/openssl.sh`.
+    fn openssl_sample() -> Sample {
+        serde_json::from_str(include_str!("testdata/openssl.json")).unwrap()
+    }
+
+    #[test]
+    fn decrypts_credentials() {
+        let sample = sample();
+        let key = PassportKey::from_pem(&sample.private_key).unwrap();
+
+        let credentials = key
+            .decrypt_credentials(&sample.passport_data.credentials)
+            .unwrap();
+        assert_eq!(credentials.nonce, "nonce-42");
+        assert!(credentials.secure_data.passport.is_some());
+        assert!(credentials.secure_data.identity_card.is_none());
+    }
+
+    #[test]
+    fn decrypts_elements() {
+        let sample = sample();
+        let key = PassportKey::from_pem(&sample.private_key).unwrap();
+
+        let data = key.decrypt(&sample.passport_data).unwrap();
+        assert_eq!(data.nonce, "nonce-42");
+        assert_eq!(data.elements.len(), 6);
+
+        let D::PersonalDetails(details) = &data.elements[0].kind else {
+            panic!("expected personal details");
+        };
+        assert_eq!(details.first_name, "Ada");
+        assert_eq!(details.last_name, "Lovelace");
+        assert_eq!(details.middle_name, None);
+        assert_eq!(
+            details.birth_date,
+            NaiveDate::from_ymd_opt(1815, 12, 10).unwrap()
+        );
+        assert_eq!(details.gender, Gender::Female);
+        assert_eq!(details.country_code, CountryCode::GB);
+        assert!(data.elements[0].data_hash.is_some());
+
+        let D::Passport(passport) = &data.elements[1].kind else {
+            panic!("expected a passport");
+        };
+        assert_eq!(passport.data.document_no, "AB123456");
+        assert_eq!(
+            passport.data.expiry_date,
+            NaiveDate::from_ymd_opt(2030, 1, 1)
+        );
+        assert!(passport.reverse_side.is_none());
+        assert!(passport.translation.is_empty());
+
+        let front_side = passport.front_side.as_ref().unwrap();
+        let contents = base64_decode(&sample.files[&front_side.file.id]);
+        assert_eq!(front_side.decrypt(&contents).unwrap(), b"front side scan");
+        let selfie = passport.selfie.as_ref().unwrap();
+        let contents = base64_decode(&sample.files[&selfie.file.id]);
+        assert_eq!(selfie.decrypt(&contents).unwrap(), b"selfie photo");
+
+        let D::Address(address) = &data.elements[2].kind else {
+            panic!("expected an address");
+        };
+        assert_eq!(address.city, "London");
+        assert_eq!(address.post_code, "SW1Y 4JH");
+
+        let D::UtilityBill(bill) = &data.elements[3].kind else {
+            panic!("expected a utility bill");
+        };
+        let contents = base64_decode(&sample.files[&bill.files[0].file.id]);
+        assert_eq!(
+            bill.files[0].decrypt(&contents).unwrap(),
+            b"utility bill scan"
+        );
+        assert_eq!(data.elements[3].data_hash, None);
+
+        assert_eq!(
+            data.elements[4].kind,
+            D::PhoneNumber("447700900000".to_owned())
+        );
+        assert_eq!(
+            data.elements[5].kind,
+            D::Email("ada@example.com".to_owned())
+        );
+    }
+
+    #[test]
+    fn decrypts_openssl_sample() {
+        let sample = openssl_sample();
+        let key = PassportKey::from_pem(&sample.private_key).unwrap();
+
+        let data = key.decrypt(&sample.passport_data).unwrap();
+        assert_eq!(data.nonce, "nonce-openssl");
+        assert_eq!(data.elements.len(), 2);
+
+        let decrypt = |file: &crate::EncryptedPassportFile| {
+            let contents = base64_decode(&sample.files[&file.file.id]);
+            file.decrypt(&contents).unwrap()
+        };
+
+        let D::DriverLicense(license) = &data.elements[0].kind else {
+            panic!("expected a driver license");
+        };
+        assert_eq!(license.data.document_no, "D1234567");
+        assert_eq!(
+            license.data.expiry_date,
+            NaiveDate::from_ymd_opt(2031, 2, 1)
+        );
+        assert_eq!(
+            decrypt(license.front_side.as_ref().unwrap()),
+            b"license front"
+        );
+        assert_eq!(
+            decrypt(license.reverse_side.as_ref().unwrap()),
+            b"license reverse"
+        );
+        assert!(license.selfie.is_none());
+        assert_eq!(decrypt(&license.translation[0]), b"license translation");
+
+        let D::BankStatement(statement) = &data.elements[1].kind else {
+            panic!("expected a bank statement");
+        };
+        let pages: Vec<_> = statement.files.iter().map(decrypt).collect();
+        assert_eq!(pages, [b"statement page 1", b"statement page 2"]);
+        assert!(statement.translation.is_empty());
+    }
+
+    #[test]
+    fn rejects_tampered_data() {
+        let mut sample = sample();
+        let key = PassportKey::from_pem(&sample.private_key).unwrap();
+
+        // personal details encrypted with the address credentials
+        let elements = &mut sample.passport_data.data;
+        let E::Address(address) = &elements[2].kind else {
+            panic!("expected an address");
+        };
+        elements[0].kind = E::PersonalDetails(EncryptedPassportElementPersonalDetails {
+            data: address.data.clone(),
+        });
+
+        let error = key.decrypt(&sample.passport_data).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<PassportError>(),
+            Some(&PassportError::HashMismatch)
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_key() {
+        let sample = sample();
+        let key = PassportKey::from_pem(&sample.private_key).unwrap();
+
+        let mut credentials = sample.passport_data.credentials;
+        credentials.secret = credentials.hash.clone();
+        let error = key.decrypt_credentials(&credentials).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<PassportError>(),
+            Some(&PassportError::InvalidSecret)
+        );
+    }
+
+    #[test]
+    fn builds_element_errors() {
+        let sample = sample();
+        let key = PassportKey::from_pem(&sample.private_key).unwrap();
+        let credentials = key
+            .decrypt_credentials(&sample.passport_data.credentials)
+            .unwrap();
+        let data = key.decrypt(&sample.passport_data).unwrap();
+
+        let passport = &data.elements[1];
+        let D::Passport(document) = &passport.kind else {
+            panic!("expected a passport");
+        };
+        let passport_credentials = credentials.secure_data.passport.unwrap();
+
+        let error = passport
+            .data_field_error("document_no", "wrong number")
+            .unwrap();
+        assert_eq!(
+            error.kind,
+            PassportElementErrorKind::DataField(PassportElementErrorDataField::new(
+                PassportElementErrorDataFieldType::Passport,
+                "document_no",
+                passport_credentials.data.unwrap().data_hash,
+            ))
+        );
+
+        let selfie = document.selfie.as_ref().unwrap();
+        let error = passport.file_error(selfie, "blurry").unwrap();
+        assert_eq!(
+            error.kind,
+            PassportElementErrorKind::Selfie(PassportElementErrorSelfie::new(
+                PassportElementErrorSelfieType::Passport,
+                passport_credentials.selfie.unwrap().file_hash,
+            ))
+        );
+
+        let bill = &data.elements[3];
+        let D::UtilityBill(documents) = &bill.kind else {
+            panic!("expected a utility bill");
+        };
+        assert!(passport.file_error(&documents.files[0], "wrong").is_none());
+        assert!(matches!(
+            bill.file_error(&documents.files[0], "expired")
+                .unwrap()
+                .kind,
+            PassportElementErrorKind::File(_)
+        ));
+        assert!(bill.data_field_error("city", "wrong").is_none());
+
+        let email = &data.elements[5];
+        assert!(email.files_error("none").is_none());
+        assert_eq!(
+            email.unspecified_error("typo").kind,
+            PassportElementErrorKind::Unspecified(PassportElementErrorUnspecified::new(
+                PassportElementErrorUnspecifiedType::Email,
+                sample.passport_data.data[5].hash.clone(),
+            ))
+        );
+    }
+
+    #[test]
+    fn sends_element_errors() {
+        let sample = sample();
+        let key = PassportKey::from_pem(&sample.private_key).unwrap();
+        let data = key.decrypt(&sample.passport_data).unwrap();
+
+        let replay = Arc::new(ReplayTransport::default());
+        replay.push_result(serde_json::json!(true));
+        let tele = Telegram::new("TOKEN".to_owned()).with_transport(replay.clone());
+
+        let errors = vec![data.elements[0].unspecified_error("try again")];
+        assert!(tele.set_passport_data_errors(UserId(42), errors).is_ok());
+
+        let body: serde_json::Value = serde_json::from_slice(&replay.requests()[0].body).unwrap();
+        assert_eq!(body["user_id"], 42);
+        assert_eq!(body["errors"][0]["source"], "unspecified");
+        assert_eq!(body["errors"][0]["type"], "personal_details");
+    }
+
+    fn base64_decode(data: &str) -> Vec<u8> {
+        super::decode(data).unwrap()
+    }
+}
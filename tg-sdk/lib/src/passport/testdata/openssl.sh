@@ -0,0 +1,99 @@
+#!/usr/bin/env bash
+# Regenerates openssl.json, Passport data encrypted with the openssl CLI as
+# described in https://core.telegram.org/passport#decrypting-data, so that
+# the tests don't only check the decryption against itself.
+set -euo pipefail
+
+cd "$(dirname "$0")"
+tmp=$(mktemp -d)
+trap 'rm -rf "$tmp"' EXIT
+
+hex() { xxd -p | tr -d '\n'; }
+b64() { base64 -w0; }
+
+openssl genrsa -out "$tmp/key.pem" 2048 2>/dev/null
+
+# encrypt <name> <plaintext>: writes $tmp/<name>.enc and sets $secret and
+# $hash, both base64.
+encrypt() {
+    local name=$1 plain=$2 len pad secret_hex hash_hex derived
+    len=${#plain}
+    pad=$(( 32 + (16 - (len + 32) % 16) % 16 + 16 * (RANDOM % 8) ))
+    {
+        printf "\\x$(printf %02x "$pad")"
+        openssl rand $(( pad - 1 ))
+        printf %s "$plain"
+    } > "$tmp/$name.padded"
+
+    secret_hex=$(openssl rand -hex 32)
+    hash_hex=$(openssl dgst -sha256 -binary "$tmp/$name.padded" | hex)
+    derived=$(printf %s "$secret_hex$hash_hex" | xxd -r -p | openssl dgst -sha512 -binary | hex)
+    openssl enc -aes-256-cbc -nopad -K "${derived:0:64}" -iv "${derived:64:32}" \
+        -in "$tmp/$name.padded" -out "$tmp/$name.enc"
+
+    secret=$(printf %s "$secret_hex" | xxd -r -p | b64)
+    hash=$(printf %s "$hash_hex" | xxd -r -p | b64)
+}
+
+file_json() {
+    printf '{"file_id":"%s","file_unique_id":"%su","file_size":%d,"file_date":1700000000}' \
+        "$1" "$1" "$(stat -c %s "$tmp/$1.enc")"
+}
+
+file_credentials() {
+    encrypt "$1" "$2"
+    printf '{"file_hash":"%s","secret":"%s"}' "$hash" "$secret"
+    printf '"%s":"%s"' "$1" "$(b64 < "$tmp/$1.enc")" >> "$tmp/files"
+    printf , >> "$tmp/files"
+}
+
+: > "$tmp/files"
+encrypt license '{"document_no":"D1234567","expiry_date":"01.02.2031"}'
+license_data=$(b64 < "$tmp/license.enc")
+license_creds="\"data\":{\"data_hash\":\"$hash\",\"secret\":\"$secret\"}"
+front=$(file_credentials front 'license front')
+reverse=$(file_credentials reverse 'license reverse')
+translation=$(file_credentials translation 'license translation')
+statement1=$(file_credentials statement1 'statement page 1')
+statement2=$(file_credentials statement2 'statement page 2')
+
+credentials=$(printf '{"secure_data":{"driver_license":{%s,"front_side":%s,"reverse_side":%s,"translation":[%s]},"bank_statement":{"files":[%s,%s]}},"nonce":"nonce-openssl"}' \
+    "$license_creds" "$front" "$reverse" "$translation" "$statement1" "$statement2")
+encrypt credentials "$credentials"
+credentials_data=$(b64 < "$tmp/credentials.enc")
+credentials_hash=$hash
+printf %s "$secret" | base64 -d > "$tmp/secret"
+credentials_secret=$(openssl pkeyutl -encrypt -pubin \
+    -inkey <(openssl rsa -in "$tmp/key.pem" -pubout 2>/dev/null) \
+    -pkeyopt rsa_padding_mode:oaep -pkeyopt rsa_oaep_md:sha1 -in "$tmp/secret" | b64)
+
+element_hash() { printf %s "$1" | openssl dgst -sha256 -binary | b64; }
+
+cat > openssl.json <<EOF
+{
+  "private_key": "$(awk '{ printf "%s\\n", $0 }' "$tmp/key.pem")",
+  "passport_data": {
+    "data": [
+      {
+        "type": "driver_license",
+        "data": "$license_data",
+        "front_side": $(file_json front),
+        "reverse_side": $(file_json reverse),
+        "translation": [$(file_json translation)],
+        "hash": "$(element_hash driver_license)"
+      },
+      {
+        "type": "bank_statement",
+        "files": [$(file_json statement1), $(file_json statement2)],
+        "hash": "$(element_hash bank_statement)"
+      }
+    ],
+    "credentials": {
+      "data": "$credentials_data",
+      "hash": "$credentials_hash",
+      "secret": "$credentials_secret"
+    }
+  },
+  "files": {$(sed 's/,$//' "$tmp/files")}
+}
+EOF
@@ -0,0 +1,17 @@
+//! Formatted text: converting between [`MessageEntity`]s and the markup of
+//! [`ParseMode`]s.
+//!
+//! [`MessageEntity`]: crate::types::MessageEntity
+//! [`ParseMode`]: crate::types::ParseMode
+
+pub use offset::*;
+pub use parse::*;
+pub use render::*;
+pub use rich::*;
+pub use split::*;
+
+mod offset;
+mod parse;
+mod render;
+mod rich;
+mod split;
@@ -0,0 +1,69 @@
+/// Converts an offset in UTF-16 code units into `text`, as Telegram counts,
+/// into an offset in bytes.
+///
+/// Returns `None` if the offset is outside of `text` or splits a character.
+///
+/// ```
+/// use tg_flows::utf16_to_utf8_offset;
+///
+/// // 👍 is 2 UTF-16 code units and 4 bytes
+/// assert_eq!(utf16_to_utf8_offset("👍 ok", 3), Some(5));
+/// assert_eq!(utf16_to_utf8_offset("👍 ok", 1), None);
+/// ```
+#[must_use]
+pub fn utf16_to_utf8_offset(text: &str, offset: usize) -> Option<usize> {
+    let mut len_utf16 = 0;
+    for (len_utf8, c) in text.char_indices() {
+        if len_utf16 >= offset {
+            return (len_utf16 == offset).then_some(len_utf8);
+        }
+        len_utf16 += c.len_utf16();
+    }
+    (len_utf16 == offset).then_some(text.len())
+}
+
+/// Converts an offset in bytes into `text` into an offset in UTF-16 code
+/// units, as Telegram counts.
+///
+/// Returns `None` if the offset is outside of `text` or splits a character.
+///
+/// ```
+/// use tg_flows::utf8_to_utf16_offset;
+///
+/// assert_eq!(utf8_to_utf16_offset("👍 ok", 5), Some(3));
+/// assert_eq!(utf8_to_utf16_offset("👍 ok", 2), None);
+/// ```
+#[must_use]
+pub fn utf8_to_utf16_offset(text: &str, offset: usize) -> Option<usize> {
+    text.get(..offset)
+        .map(|before| before.encode_utf16().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::text::{utf16_to_utf8_offset, utf8_to_utf16_offset};
+
+    proptest! {
+        #[test]
+        fn round_trips(text in any::<String>()) {
+            for (offset, _) in text.char_indices().chain([(text.len(), ' ')]) {
+                let offset_utf16 = utf8_to_utf16_offset(&text, offset).unwrap();
+                prop_assert_eq!(utf16_to_utf8_offset(&text, offset_utf16), Some(offset));
+
+                let before: Vec<_> = text.encode_utf16().take(offset_utf16).collect();
+                prop_assert_eq!(String::from_utf16(&before).unwrap(), &text[..offset]);
+            }
+        }
+
+        #[test]
+        fn rejects_invalid(text in any::<String>(), offset in 0..64_usize) {
+            let units: Vec<_> = text.encode_utf16().collect();
+            let valid = offset <= units.len()
+                && String::from_utf16(&units[..offset]).is_ok();
+            prop_assert_eq!(utf16_to_utf8_offset(&text, offset).is_some(), valid);
+            prop_assert_eq!(utf8_to_utf16_offset(&text, offset).is_some(), text.is_char_boundary(offset));
+        }
+    }
+}
@@ -0,0 +1,697 @@
+use std::{cmp::Reverse, fmt};
+
+use url::Url;
+
+use crate::types::{MessageEntity, MessageEntityKind};
+
+/// Why markup can't be parsed, and where.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarkupError {
+    /// Offset in bytes into the markup.
+    pub offset: usize,
+
+    pub kind: MarkupErrorKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MarkupErrorKind {
+    /// A tag or marker is never closed.
+    Unclosed(String),
+
+    /// A closing tag or marker has nothing to close.
+    UnexpectedClose(String),
+
+    /// The tag isn't one Telegram supports.
+    UnknownTag(String),
+
+    /// The tag lacks an attribute it needs, e.g. `href` of `<a>`.
+    MissingAttribute {
+        tag: String,
+        attribute: &'static str,
+    },
+
+    InvalidUrl(String),
+
+    /// A reserved character isn't part of markup and isn't escaped.
+    UnescapedCharacter(char),
+}
+
+impl fmt::Display for MarkupErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unclosed(name) => write!(f, "`{name}` is never closed"),
+            Self::UnexpectedClose(name) => write!(f, "`{name}` closes nothing"),
+            Self::UnknownTag(name) => write!(f, "unsupported tag <{name}>"),
+            Self::MissingAttribute { tag, attribute } => {
+                write!(f, "<{tag}> needs the `{attribute}` attribute")
+            }
+            Self::InvalidUrl(url) => write!(f, "invalid URL {url:?}"),
+            Self::UnescapedCharacter(c) => write!(f, "{c:?} must be escaped"),
+        }
+    }
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+/// Parses [`ParseMode::Html`] markup into plain text and the entities
+/// formatting it, the way Telegram does.
+///
+/// This catches markup Telegram would refuse with "can't parse entities"
+/// before sending, and the result can be sent with `entities` instead.
+///
+/// ```
+/// use tg_flows::{parse_html, MessageEntity};
+///
+/// let (text, entities) = parse_html("<b>1 &lt; <i>2</i></b>").unwrap();
+/// assert_eq!(text, "1 < 2");
+/// assert_eq!(entities, [MessageEntity::bold(0, 5), MessageEntity::italic(4, 1)]);
+///
+/// let error = parse_html("<b>1 < 2</b>").unwrap_err();
+/// assert_eq!(error.to_string(), "'<' must be escaped at byte 5");
+/// ```
+///
+/// [`ParseMode::Html`]: crate::types::ParseMode::Html
+pub fn parse_html(html: &str) -> Result<(String, Vec<MessageEntity>), MarkupError> {
+    let mut parser = Parser::new(html.len());
+    let mut written = 0;
+
+    while let Some(found) = html[written..].find(['<', '&']) {
+        let at = written + found;
+        parser.push_str(&html[written..at]);
+
+        if html[at..].starts_with('&') {
+            match decode_reference(&html[at..]) {
+                Some((c, len)) => {
+                    parser.push(c);
+                    written = at + len;
+                }
+                None => {
+                    parser.push('&');
+                    written = at + 1;
+                }
+            }
+            continue;
+        }
+
+        let is_tag = html[at + 1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/');
+        let end = html[at..]
+            .find('>')
+            .map(|end| at + end)
+            .filter(|_| is_tag)
+            .ok_or_else(|| error(at, MarkupErrorKind::UnescapedCharacter('<')))?;
+        let tag = &html[at + 1..end];
+        written = end + 1;
+
+        if let Some(name) = tag.strip_prefix('/') {
+            parser.close(&name.trim().to_lowercase(), at)?;
+            continue;
+        }
+
+        let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+        let name = tag[..name_end].to_lowercase();
+        let attributes = parse_attributes(&tag[name_end..]);
+        let attribute = |attribute: &'static str| {
+            let value = attributes.iter().find(|(name, _)| name == attribute);
+            value.map(|(_, value)| value.as_str())
+        };
+        let missing = |attribute| {
+            let tag = name.clone();
+            error(at, MarkupErrorKind::MissingAttribute { tag, attribute })
+        };
+
+        let kind = match name.as_str() {
+            "b" | "strong" => MessageEntityKind::Bold,
+            "i" | "em" => MessageEntityKind::Italic,
+            "u" | "ins" => MessageEntityKind::Underline,
+            "s" | "strike" | "del" => MessageEntityKind::Strikethrough,
+            "tg-spoiler" => MessageEntityKind::Spoiler,
+            "span" if attribute("class") == Some("tg-spoiler") => MessageEntityKind::Spoiler,
+            "span" => return Err(missing("class")),
+            "pre" => MessageEntityKind::Pre { language: None },
+            "code" => {
+                let language = attribute("class").and_then(|c| c.strip_prefix("language-"));
+                if let Some(language) = language {
+                    if parser.set_pre_language(language) {
+                        parser.open(name, None, at);
+                        continue;
+                    }
+                }
+                MessageEntityKind::Code
+            }
+            "a" => {
+                let href = attribute("href").ok_or_else(|| missing("href"))?;
+                MessageEntityKind::TextLink {
+                    url: parse_url(href, at)?,
+                }
+            }
+            "tg-emoji" => {
+                let id = attribute("emoji-id").ok_or_else(|| missing("emoji-id"))?;
+                MessageEntityKind::CustomEmoji {
+                    custom_emoji_id: id.to_owned(),
+                }
+            }
+            _ => return Err(error(at, MarkupErrorKind::UnknownTag(name))),
+        };
+        parser.open(name, Some(kind), at);
+    }
+
+    parser.push_str(&html[written..]);
+    parser.finish()
+}
+
+/// Parses [`ParseMode::MarkdownV2`] markup into plain text and the entities
+/// formatting it, the way Telegram does.
+///
+/// This catches markup Telegram would refuse with "can't parse entities"
+/// before sending, and the result can be sent with `entities` instead.
+///
+/// ```
+/// use tg_flows::{parse_markdown, MessageEntity};
+///
+/// let (text, entities) = parse_markdown(r"*1 \+ _1_* `= 2`").unwrap();
+/// assert_eq!(text, "1 + 1 = 2");
+/// assert_eq!(
+///     entities,
+///     [MessageEntity::bold(0, 5), MessageEntity::italic(4, 1), MessageEntity::code(6, 3)]
+/// );
+///
+/// let error = parse_markdown("*1 + 1* = 2").unwrap_err();
+/// assert_eq!(error.to_string(), "'+' must be escaped at byte 3");
+/// ```
+///
+/// [`ParseMode::MarkdownV2`]: crate::types::ParseMode::MarkdownV2
+pub fn parse_markdown(markdown: &str) -> Result<(String, Vec<MessageEntity>), MarkupError> {
+    let mut parser = Parser::new(markdown.len());
+    let mut chars = markdown.char_indices().peekable();
+
+    while let Some((at, c)) = chars.next() {
+        let rest = &markdown[at..];
+        let in_code = parser.in_code();
+
+        match c {
+            '\\' => match chars.next() {
+                Some((_, escaped)) if ('\u{1}'..='\u{7e}').contains(&escaped) => {
+                    parser.push(escaped)
+                }
+                _ => return Err(error(at, MarkupErrorKind::UnescapedCharacter('\\'))),
+            },
+            '`' if rest.starts_with("```") && !parser.is_open("`") => {
+                chars.next();
+                chars.next();
+                if parser.is_open("```") {
+                    parser.close("```", at)?;
+                    continue;
+                }
+
+                // The rest of the line is the language, if it's a single word.
+                let body = &rest[3..];
+                let line = body.find('\n').map(|end| &body[..end]);
+                let language =
+                    line.filter(|line| !line.contains(|c: char| c.is_whitespace() || c == '`'));
+                if let Some(language) = language {
+                    for _ in 0..=language.chars().count() {
+                        chars.next();
+                    }
+                }
+                let language = language.filter(|l| !l.is_empty()).map(str::to_owned);
+                parser.open(
+                    "```".to_owned(),
+                    Some(MessageEntityKind::Pre { language }),
+                    at,
+                );
+            }
+            '`' if parser.is_open("`") => parser.close("`", at)?,
+            '`' if !in_code => parser.open("`".to_owned(), Some(MessageEntityKind::Code), at),
+            c if in_code => {
+                if c == '`' {
+                    return Err(error(at, MarkupErrorKind::UnescapedCharacter(c)));
+                }
+                parser.push(c);
+            }
+            '*' => parser.toggle("*", MessageEntityKind::Bold, at)?,
+            '~' => parser.toggle("~", MessageEntityKind::Strikethrough, at)?,
+            '|' if chars.next_if(|&(_, c)| c == '|').is_some() => {
+                parser.toggle("||", MessageEntityKind::Spoiler, at)?
+            }
+            '_' => {
+                match chars.next_if(|&(_, c)| c == '_') {
+                    Some(_) => parser.toggle("__", MessageEntityKind::Underline, at)?,
+                    None => parser.toggle("_", MessageEntityKind::Italic, at)?,
+                }
+                // `\r` separates `_` from `__`, and isn't part of the text.
+                chars.next_if(|&(_, c)| c == '\r');
+            }
+            '[' => parser.open("[".to_owned(), None, at),
+            '!' if chars.next_if(|&(_, c)| c == '[').is_some() => {
+                parser.open("![".to_owned(), None, at)
+            }
+            ']' if matches!(parser.innermost(), Some("[" | "![")) => {
+                let is_emoji = parser.innermost() == Some("![");
+                let url_start = at + 1;
+                if chars.next_if(|&(_, c)| c == '(').is_none() {
+                    return Err(error(url_start, MarkupErrorKind::Unclosed("](".to_owned())));
+                }
+
+                let mut url = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, ')')) => break,
+                        Some((_, '\\')) => url.extend(chars.next().map(|(_, c)| c)),
+                        Some((_, c)) => url.push(c),
+                        None => {
+                            return Err(error(url_start, MarkupErrorKind::Unclosed("(".to_owned())))
+                        }
+                    }
+                }
+
+                let kind = match is_emoji {
+                    false => MessageEntityKind::TextLink {
+                        url: parse_url(&url, url_start)?,
+                    },
+                    true => {
+                        let id = parse_url(&url, url_start)?
+                            .query_pairs()
+                            .find(|(name, _)| name == "id")
+                            .filter(|_| url.starts_with("tg://emoji"))
+                            .map(|(_, id)| id.into_owned());
+                        let id =
+                            id.ok_or_else(|| error(url_start, MarkupErrorKind::InvalidUrl(url)))?;
+                        MessageEntityKind::CustomEmoji {
+                            custom_emoji_id: id,
+                        }
+                    }
+                };
+                parser.set_kind(kind);
+                parser.close(if is_emoji { "![" } else { "[" }, at)?;
+            }
+            // The rest of the reserved characters, the others are markup.
+            ']' | '(' | ')' | '`' | '>' | '#' | '+' | '-' | '=' | '|' | '{' | '}' | '.' | '!' => {
+                return Err(error(at, MarkupErrorKind::UnescapedCharacter(c)))
+            }
+            c => parser.push(c),
+        }
+    }
+
+    parser.finish()
+}
+
+fn error(offset: usize, kind: MarkupErrorKind) -> MarkupError {
+    MarkupError { offset, kind }
+}
+
+fn parse_url(url: &str, at: usize) -> Result<Url, MarkupError> {
+    Url::parse(url).map_err(|_| error(at, MarkupErrorKind::InvalidUrl(url.to_owned())))
+}
+
+/// Decodes the character reference `&...;` `s` starts with, returning the
+/// character and the length of the reference.
+fn decode_reference(s: &str) -> Option<(char, usize)> {
+    let end = s.get(..12).unwrap_or(s).find(';')?;
+    let c = match &s[1..end] {
+        "lt" => '<',
+        "gt" => '>',
+        "amp" => '&',
+        "quot" => '"',
+        number => {
+            let number = number.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)?
+        }
+    };
+    Some((c, end + 1))
+}
+
+/// Parses `name="value"` pairs of a tag, decoding character references.
+fn parse_attributes(mut s: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+
+    loop {
+        s = s.trim_start();
+        let name_end = s
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(s.len());
+        if name_end == 0 {
+            break attributes;
+        }
+        let name = s[..name_end].to_lowercase();
+        s = s[name_end..].trim_start();
+
+        let Some(value) = s.strip_prefix('=') else {
+            attributes.push((name, String::new()));
+            continue;
+        };
+        let value = value.trim_start();
+        let (raw, rest) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let value = &value[1..];
+                let end = value.find(quote).unwrap_or(value.len());
+                (&value[..end], value.get(end + 1..).unwrap_or_default())
+            }
+            _ => {
+                let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                (&value[..end], &value[end..])
+            }
+        };
+        s = rest;
+
+        let mut decoded = String::with_capacity(raw.len());
+        let mut raw = raw;
+        while let Some(at) = raw.find('&') {
+            decoded.push_str(&raw[..at]);
+            match decode_reference(&raw[at..]) {
+                Some((c, len)) => {
+                    decoded.push(c);
+                    raw = &raw[at + len..];
+                }
+                None => {
+                    decoded.push('&');
+                    raw = &raw[at + 1..];
+                }
+            }
+        }
+        decoded.push_str(raw);
+        attributes.push((name, decoded));
+    }
+}
+
+struct Open {
+    /// The tag or marker, to match it with its end.
+    name: String,
+
+    /// `None` for a `<code>` giving the language of `<pre>`, or a link whose
+    /// URL comes at its end.
+    kind: Option<MessageEntityKind>,
+
+    /// Where it is in the markup.
+    at: usize,
+
+    /// Where it is in the text, in UTF-16 code units.
+    start: usize,
+}
+
+struct Parser {
+    text: String,
+    len_utf16: usize,
+
+    /// Entities with where they start in the markup.
+    entities: Vec<(usize, MessageEntity)>,
+    open: Vec<Open>,
+}
+
+impl Parser {
+    fn new(capacity: usize) -> Self {
+        Self {
+            text: String::with_capacity(capacity),
+            len_utf16: 0,
+            entities: Vec::new(),
+            open: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+        self.len_utf16 += c.len_utf16();
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.text.push_str(s);
+        self.len_utf16 += s.encode_utf16().count();
+    }
+
+    fn in_code(&self) -> bool {
+        self.open.iter().any(|open| {
+            matches!(
+                open.kind,
+                Some(MessageEntityKind::Code | MessageEntityKind::Pre { .. })
+            )
+        })
+    }
+
+    fn innermost(&self) -> Option<&str> {
+        self.open.last().map(|open| open.name.as_str())
+    }
+
+    fn is_open(&self, name: &str) -> bool {
+        self.open.iter().any(|open| open.name == name)
+    }
+
+    fn open(&mut self, name: String, kind: Option<MessageEntityKind>, at: usize) {
+        self.open.push(Open {
+            name,
+            kind,
+            at,
+            start: self.len_utf16,
+        });
+    }
+
+    /// Sets the language of the `<pre>` just opened, returning `false` if
+    /// there is no such `<pre>`.
+    fn set_pre_language(&mut self, language: &str) -> bool {
+        match self.open.last_mut() {
+            Some(Open {
+                kind: Some(MessageEntityKind::Pre { language: l @ None }),
+                start,
+                ..
+            }) if *start == self.len_utf16 => {
+                *l = Some(language.to_owned());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Sets the kind of the innermost entity, once it is known.
+    fn set_kind(&mut self, kind: MessageEntityKind) {
+        if let Some(open) = self.open.last_mut() {
+            open.kind = Some(kind);
+        }
+    }
+
+    fn toggle(
+        &mut self,
+        name: &str,
+        kind: MessageEntityKind,
+        at: usize,
+    ) -> Result<(), MarkupError> {
+        match self.is_open(name) {
+            true => self.close(name, at),
+            false => {
+                self.open(name.to_owned(), Some(kind), at);
+                Ok(())
+            }
+        }
+    }
+
+    /// Closes the innermost entity, which has to be `name`.
+    fn close(&mut self, name: &str, at: usize) -> Result<(), MarkupError> {
+        let Some(open) = self.open.pop() else {
+            return Err(error(at, MarkupErrorKind::UnexpectedClose(name.to_owned())));
+        };
+        if open.name != name {
+            let kind = match self.is_open(name) {
+                true => MarkupErrorKind::Unclosed(open.name),
+                false => MarkupErrorKind::UnexpectedClose(name.to_owned()),
+            };
+            let offset = if self.is_open(name) { open.at } else { at };
+            return Err(error(offset, kind));
+        }
+
+        let length = self.len_utf16 - open.start;
+        if let (Some(kind), 1..) = (open.kind, length) {
+            let entity = MessageEntity::new(kind, open.start, length);
+            self.entities.push((open.at, entity));
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(String, Vec<MessageEntity>), MarkupError> {
+        if let Some(open) = self.open.pop() {
+            return Err(error(open.at, MarkupErrorKind::Unclosed(open.name)));
+        }
+
+        // Outer entities first, as Telegram sends them.
+        self.entities
+            .sort_by_key(|(at, e)| (e.offset, Reverse(e.length), *at));
+        let entities = self.entities.into_iter().map(|(_, e)| e).collect();
+        Ok((self.text, entities))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        text::{parse_html, parse_markdown, render_html, render_markdown, MarkupErrorKind},
+        types::{MessageEntity, MessageEntityKind},
+    };
+
+    fn link(url: &str, offset: usize, length: usize) -> MessageEntity {
+        MessageEntity::text_link(url.parse().unwrap(), offset, length)
+    }
+
+    fn emoji(offset: usize, length: usize) -> MessageEntity {
+        MessageEntity::custom_emoji("5368324170671202286".to_owned(), offset, length)
+    }
+
+    fn html_error(html: &str) -> (usize, MarkupErrorKind) {
+        let error = parse_html(html).unwrap_err();
+        (error.offset, error.kind)
+    }
+
+    fn markdown_error(markdown: &str) -> (usize, MarkupErrorKind) {
+        let error = parse_markdown(markdown).unwrap_err();
+        (error.offset, error.kind)
+    }
+
+    #[test]
+    fn html() {
+        let (text, entities) = parse_html(
+            "<strong>b</strong> <em>i</em> <ins>u</ins> <del>s</del> \
+             <span class=\"tg-spoiler\">p</span> <code>c</code> \
+             <pre><code class=\"language-rust\">rust</code></pre> <pre>pre</pre> \
+             <a href='https://example.com/?a=1&amp;b=2'>link</a> \
+             <tg-emoji emoji-id=\"5368324170671202286\">👍</tg-emoji> &#128077;&#x1F44D;&foo",
+        )
+        .unwrap();
+
+        assert_eq!(text, "b i u s p c rust pre link 👍 👍👍&foo");
+        assert_eq!(
+            entities,
+            [
+                MessageEntity::bold(0, 1),
+                MessageEntity::italic(2, 1),
+                MessageEntity::underline(4, 1),
+                MessageEntity::strikethrough(6, 1),
+                MessageEntity::spoiler(8, 1),
+                MessageEntity::code(10, 1),
+                MessageEntity::pre(Some("rust".to_owned()), 12, 4),
+                MessageEntity::pre(None, 17, 3),
+                link("https://example.com/?a=1&b=2", 21, 4),
+                emoji(26, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn html_errors() {
+        use MarkupErrorKind::*;
+
+        assert_eq!(html_error("a <b>b"), (2, Unclosed("b".to_owned())));
+        assert_eq!(html_error("a</b>"), (1, UnexpectedClose("b".to_owned())));
+        assert_eq!(html_error("<b><i>a</b></i>"), (3, Unclosed("i".to_owned())));
+        assert_eq!(
+            html_error("<b><i>a</u></i>"),
+            (7, UnexpectedClose("u".to_owned()))
+        );
+        assert_eq!(html_error("x <blink>"), (2, UnknownTag("blink".to_owned())));
+        assert_eq!(
+            html_error("<a>x</a>"),
+            (
+                0,
+                MissingAttribute {
+                    tag: "a".to_owned(),
+                    attribute: "href"
+                }
+            )
+        );
+        assert_eq!(
+            html_error("<a href=\"nope\">x</a>"),
+            (0, InvalidUrl("nope".to_owned()))
+        );
+        assert_eq!(html_error("1 < 2"), (2, UnescapedCharacter('<')));
+    }
+
+    #[test]
+    fn markdown() {
+        let (text, entities) = parse_markdown(
+            "*b* _i_ __u__ ~s~ ||p|| `c \\` d` ```rust\nfn x() {}``` ```pre``` \
+             [link](https://example.com/a_\\(b\\)) ![👍](tg://emoji?id=5368324170671202286) \\.",
+        )
+        .unwrap();
+
+        assert_eq!(text, "b i u s p c ` d fn x() {} pre link 👍 .");
+        assert_eq!(
+            entities,
+            [
+                MessageEntity::bold(0, 1),
+                MessageEntity::italic(2, 1),
+                MessageEntity::underline(4, 1),
+                MessageEntity::strikethrough(6, 1),
+                MessageEntity::spoiler(8, 1),
+                MessageEntity::code(10, 5),
+                MessageEntity::pre(Some("rust".to_owned()), 16, 9),
+                MessageEntity::pre(None, 26, 3),
+                link("https://example.com/a_(b)", 30, 4),
+                emoji(35, 2),
+            ]
+        );
+
+        let (text, entities) = parse_markdown("___a_\r__").unwrap();
+        assert_eq!(text, "a");
+        assert_eq!(
+            entities,
+            [MessageEntity::underline(0, 1), MessageEntity::italic(0, 1)]
+        );
+    }
+
+    #[test]
+    fn markdown_errors() {
+        use MarkupErrorKind::*;
+
+        assert_eq!(markdown_error("a *b"), (2, Unclosed("*".to_owned())));
+        assert_eq!(markdown_error("*_a*_"), (1, Unclosed("_".to_owned())));
+        assert_eq!(markdown_error("1.5"), (1, UnescapedCharacter('.')));
+        assert_eq!(markdown_error("a | b"), (2, UnescapedCharacter('|')));
+        assert_eq!(markdown_error("`a"), (0, Unclosed("`".to_owned())));
+        assert_eq!(markdown_error("[a]b"), (3, Unclosed("](".to_owned())));
+        assert_eq!(markdown_error("[a](b"), (3, Unclosed("(".to_owned())));
+        assert_eq!(markdown_error("[a](b)"), (3, InvalidUrl("b".to_owned())));
+        assert_eq!(
+            markdown_error("![a](https://example.com)"),
+            (4, InvalidUrl("https://example.com".to_owned()))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "a < b_* ( c 👍 d `e` \\ f";
+        let entities = vec![
+            MessageEntity::bold(0, 12),
+            MessageEntity::italic(2, 5),
+            MessageEntity::underline(2, 2),
+            MessageEntity::code(17, 3),
+            link("https://example.com/)", 21, 3),
+        ];
+
+        assert_eq!(
+            parse_html(&render_html(text, &entities)).unwrap(),
+            (text.to_owned(), entities.clone())
+        );
+        assert_eq!(
+            parse_markdown(&render_markdown(text, &entities)).unwrap(),
+            (text.to_owned(), entities)
+        );
+
+        let overlapping = [
+            MessageEntity::bold(0, 3),
+            MessageEntity::new(MessageEntityKind::Italic, 2, 3),
+        ];
+        let (_, entities) = parse_markdown(&render_markdown("abcde", &overlapping)).unwrap();
+        assert_eq!(
+            entities,
+            [
+                MessageEntity::bold(0, 3),
+                MessageEntity::italic(2, 1),
+                MessageEntity::italic(3, 2)
+            ]
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use std::{cmp::Reverse, ops::Range};
+
+use crate::types::{MessageEntity, MessageEntityKind, MessageEntityRef};
+
+/// Renders `text` formatted with `entities` as [`ParseMode::Html`] markup.
+///
+/// Overlapping entities are split, so that tags nest. Entities Telegram
+/// detects by itself, like mentions or URLs, are left as plain text, and so is
+/// all of `text` if an entity doesn't fit it.
+///
+/// ```
+/// use tg_flows::{render_html, MessageEntity};
+///
+/// let entities = [MessageEntity::bold(0, 9), MessageEntity::italic(6, 3)];
+/// assert_eq!(
+///     render_html("a < b & c", &entities),
+///     "<b>a &lt; b <i>&amp; c</i></b>"
+/// );
+/// ```
+///
+/// [`ParseMode::Html`]: crate::types::ParseMode::Html
+#[must_use]
+pub fn render_html(text: &str, entities: &[MessageEntity]) -> String {
+    render(Syntax::Html, text, entities)
+}
+
+/// Renders `text` formatted with `entities` as [`ParseMode::MarkdownV2`]
+/// markup.
+///
+/// Overlapping entities are split, so that markers nest. Entities Telegram
+/// detects by itself, like mentions or URLs, are left as plain text, and so is
+/// all of `text` if an entity doesn't fit it.
+///
+/// ```
+/// use tg_flows::{render_markdown, MessageEntity};
+///
+/// let entities = [MessageEntity::bold(0, 5), MessageEntity::code(6, 5)];
+/// assert_eq!(
+///     render_markdown("1 + 1 `= 2`", &entities),
+///     r"*1 \+ 1* `\`= 2\``"
+/// );
+/// ```
+///
+/// [`ParseMode::MarkdownV2`]: crate::types::ParseMode::MarkdownV2
+#[must_use]
+pub fn render_markdown(text: &str, entities: &[MessageEntity]) -> String {
+    render(Syntax::MarkdownV2, text, entities)
+}
+
+#[derive(Clone, Copy)]
+enum Syntax {
+    Html,
+    MarkdownV2,
+}
+
+/// An entity which changes how text looks, with its UTF-8 range.
+struct Span<'a> {
+    range: Range<usize>,
+    kind: &'a MessageEntityKind,
+}
+
+impl Span<'_> {
+    fn is_code(&self) -> bool {
+        matches!(
+            self.kind,
+            MessageEntityKind::Code | MessageEntityKind::Pre { .. }
+        )
+    }
+}
+
+fn render(syntax: Syntax, text: &str, entities: &[MessageEntity]) -> String {
+    let spans: Vec<_> = MessageEntityRef::try_parse(text, entities)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entity| !entity.range().is_empty() && is_markup(entity.kind()))
+        .map(|entity| Span {
+            range: entity.range(),
+            kind: entity.kind(),
+        })
+        .collect();
+
+    let mut boundaries: Vec<_> = spans
+        .iter()
+        .flat_map(|span| [span.range.start, span.range.end])
+        .collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut writer = Writer {
+        syntax,
+        out: String::with_capacity(text.len()),
+        marker_end: None,
+    };
+    // Indices of the open spans, innermost last.
+    let mut open: Vec<usize> = Vec::new();
+    let mut written = 0;
+
+    for at in boundaries {
+        let in_code = open.iter().any(|&i| spans[i].is_code());
+        writer.text(&text[written..at], in_code);
+        written = at;
+
+        // Close the spans ending here, and the ones opened inside of them,
+        // which are reopened after.
+        if let Some(first) = open.iter().position(|&i| spans[i].range.end == at) {
+            let closed = open.split_off(first);
+            for &i in closed.iter().rev() {
+                writer.close(spans[i].kind);
+            }
+            for i in closed {
+                if spans[i].range.end != at {
+                    writer.open(spans[i].kind);
+                    open.push(i);
+                }
+            }
+        }
+
+        // Open the longest spans first, so that shorter ones nest inside.
+        let mut starting: Vec<_> = (0..spans.len())
+            .filter(|&i| spans[i].range.start == at)
+            .collect();
+        starting.sort_by_key(|&i| (Reverse(spans[i].range.end), i));
+        for i in starting {
+            writer.open(spans[i].kind);
+            open.push(i);
+        }
+    }
+
+    writer.text(&text[written..], false);
+    writer.out
+}
+
+/// Returns `true` for entities that have to be written as markup, as opposed
+/// to the ones Telegram detects in plain text.
+fn is_markup(kind: &MessageEntityKind) -> bool {
+    use MessageEntityKind::*;
+
+    match kind {
+        Bold
+        | Italic
+        | Underline
+        | Strikethrough
+        | Spoiler
+        | Code
+        | Pre { .. }
+        | TextLink { .. }
+        | TextMention { .. }
+        | CustomEmoji { .. } => true,
+        Mention | Hashtag | Cashtag | BotCommand | Url | Email | PhoneNumber => false,
+    }
+}
+
+struct Writer {
+    syntax: Syntax,
+    out: String,
+
+    /// Where the last markup written ends, to tell `_` of italic and `__` of
+    /// underline apart when they touch.
+    marker_end: Option<usize>,
+}
+
+impl Writer {
+    fn text(&mut self, text: &str, in_code: bool) {
+        match self.syntax {
+            Syntax::Html => escape_html(&mut self.out, text),
+            Syntax::MarkdownV2 => escape_markdown(&mut self.out, text, in_code),
+        }
+    }
+
+    fn open(&mut self, kind: &MessageEntityKind) {
+        use MessageEntityKind::*;
+
+        let markup = match (self.syntax, kind) {
+            (Syntax::Html, Bold) => "<b>".to_owned(),
+            (Syntax::Html, Italic) => "<i>".to_owned(),
+            (Syntax::Html, Underline) => "<u>".to_owned(),
+            (Syntax::Html, Strikethrough) => "<s>".to_owned(),
+            (Syntax::Html, Spoiler) => "<tg-spoiler>".to_owned(),
+            (Syntax::Html, Code) => "<code>".to_owned(),
+            (Syntax::Html, Pre { language: None }) => "<pre>".to_owned(),
+            (
+                Syntax::Html,
+                Pre {
+                    language: Some(language),
+                },
+            ) => {
+                let mut markup = String::from("<pre><code class=\"language-");
+                escape_html(&mut markup, language);
+                markup + "\">"
+            }
+            (Syntax::Html, TextLink { url }) => html_link(url.as_str()),
+            (Syntax::Html, TextMention { user }) => html_link(user.id.url().as_str()),
+            (Syntax::Html, CustomEmoji { custom_emoji_id }) => {
+                let mut markup = String::from("<tg-emoji emoji-id=\"");
+                escape_html(&mut markup, custom_emoji_id);
+                markup + "\">"
+            }
+
+            (Syntax::MarkdownV2, Bold) => "*".to_owned(),
+            (Syntax::MarkdownV2, Italic) => "_".to_owned(),
+            (Syntax::MarkdownV2, Underline) => "__".to_owned(),
+            (Syntax::MarkdownV2, Strikethrough) => "~".to_owned(),
+            (Syntax::MarkdownV2, Spoiler) => "||".to_owned(),
+            (Syntax::MarkdownV2, Code) => "`".to_owned(),
+            (Syntax::MarkdownV2, Pre { language }) => {
+                format!("```{}\n", language.as_deref().unwrap_or_default())
+            }
+            (Syntax::MarkdownV2, TextLink { .. } | TextMention { .. }) => "[".to_owned(),
+            (Syntax::MarkdownV2, CustomEmoji { .. }) => "![".to_owned(),
+
+            (_, Mention | Hashtag | Cashtag | BotCommand | Url | Email | PhoneNumber) => return,
+        };
+        self.markup(&markup);
+    }
+
+    fn close(&mut self, kind: &MessageEntityKind) {
+        use MessageEntityKind::*;
+
+        let markup = match (self.syntax, kind) {
+            (Syntax::Html, Bold) => "</b>".to_owned(),
+            (Syntax::Html, Italic) => "</i>".to_owned(),
+            (Syntax::Html, Underline) => "</u>".to_owned(),
+            (Syntax::Html, Strikethrough) => "</s>".to_owned(),
+            (Syntax::Html, Spoiler) => "</tg-spoiler>".to_owned(),
+            (Syntax::Html, Code) => "</code>".to_owned(),
+            (Syntax::Html, Pre { language: None }) => "</pre>".to_owned(),
+            (Syntax::Html, Pre { language: Some(_) }) => "</code></pre>".to_owned(),
+            (Syntax::Html, TextLink { .. } | TextMention { .. }) => "</a>".to_owned(),
+            (Syntax::Html, CustomEmoji { .. }) => "</tg-emoji>".to_owned(),
+
+            (Syntax::MarkdownV2, Bold) => "*".to_owned(),
+            (Syntax::MarkdownV2, Italic) => "_".to_owned(),
+            (Syntax::MarkdownV2, Underline) => "__".to_owned(),
+            (Syntax::MarkdownV2, Strikethrough) => "~".to_owned(),
+            (Syntax::MarkdownV2, Spoiler) => "||".to_owned(),
+            (Syntax::MarkdownV2, Code) => "`".to_owned(),
+            (Syntax::MarkdownV2, Pre { .. }) => "```".to_owned(),
+            (Syntax::MarkdownV2, TextLink { url }) => markdown_link(url.as_str()),
+            (Syntax::MarkdownV2, TextMention { user }) => markdown_link(user.id.url().as_str()),
+            (Syntax::MarkdownV2, CustomEmoji { custom_emoji_id }) => {
+                markdown_link(&format!("tg://emoji?id={custom_emoji_id}"))
+            }
+
+            (_, Mention | Hashtag | Cashtag | BotCommand | Url | Email | PhoneNumber) => return,
+        };
+        self.markup(&markup);
+    }
+
+    fn markup(&mut self, markup: &str) {
+        // `___` is always read as `__` first, which breaks italic touching
+        // underline, so they are separated with an ignored `\r`.
+        if self.marker_end == Some(self.out.len())
+            && self.out.ends_with('_')
+            && markup.starts_with('_')
+        {
+            self.out.push('\r');
+        }
+        self.out.push_str(markup);
+        self.marker_end = Some(self.out.len());
+    }
+}
+
+fn html_link(url: &str) -> String {
+    let mut markup = String::from("<a href=\"");
+    escape_html(&mut markup, url);
+    markup + "\">"
+}
+
+fn markdown_link(url: &str) -> String {
+    let mut markup = String::from("](");
+    for c in url.chars() {
+        if matches!(c, ')' | '\\') {
+            markup.push('\\');
+        }
+        markup.push(c);
+    }
+    markup + ")"
+}
+
+fn escape_html(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn escape_markdown(out: &mut String, text: &str, in_code: bool) {
+    for c in text.chars() {
+        let reserved = match in_code {
+            true => matches!(c, '`' | '\\'),
+            false => matches!(
+                c,
+                '_' | '*'
+                    | '['
+                    | ']'
+                    | '('
+                    | ')'
+                    | '~'
+                    | '`'
+                    | '>'
+                    | '#'
+                    | '+'
+                    | '-'
+                    | '='
+                    | '|'
+                    | '{'
+                    | '}'
+                    | '.'
+                    | '!'
+                    | '\\'
+            ),
+        };
+        if reserved {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        text::{render_html, render_markdown},
+        types::{MessageEntity, User, UserId},
+    };
+
+    fn link(offset: usize, length: usize) -> MessageEntity {
+        let url = "https://example.com/a_(b)".parse().unwrap();
+        MessageEntity::text_link(url, offset, length)
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            render_html("a <b> & \"c\"", &[]),
+            "a &lt;b&gt; &amp; &quot;c&quot;"
+        );
+        assert_eq!(
+            render_markdown("1.5 * (2 - 1) = _x_!", &[]),
+            r"1\.5 \* \(2 \- 1\) \= \_x\_\!"
+        );
+    }
+
+    #[test]
+    fn kinds() {
+        let user = User {
+            id: UserId(42),
+            is_bot: false,
+            first_name: "Ann".to_owned(),
+            last_name: None,
+            username: None,
+            language_code: None,
+            is_premium: false,
+            added_to_attachment_menu: false,
+        };
+        let text = "b i u s p c rust link Ann 👍";
+        let entities = [
+            MessageEntity::bold(0, 1),
+            MessageEntity::italic(2, 1),
+            MessageEntity::underline(4, 1),
+            MessageEntity::strikethrough(6, 1),
+            MessageEntity::spoiler(8, 1),
+            MessageEntity::code(10, 1),
+            MessageEntity::pre(Some("rust".to_owned()), 12, 4),
+            link(17, 4),
+            MessageEntity::text_mention(user, 22, 3),
+            MessageEntity::custom_emoji("5368324170671202286".to_owned(), 26, 2),
+        ];
+
+        assert_eq!(
+            render_html(text, &entities),
+            "<b>b</b> <i>i</i> <u>u</u> <s>s</s> <tg-spoiler>p</tg-spoiler> <code>c</code> \
+             <pre><code class=\"language-rust\">rust</code></pre> \
+             <a href=\"https://example.com/a_(b)\">link</a> \
+             <a href=\"tg://user/?id=42\">Ann</a> \
+             <tg-emoji emoji-id=\"5368324170671202286\">👍</tg-emoji>"
+        );
+        assert_eq!(
+            render_markdown(text, &entities),
+            "*b* _i_ __u__ ~s~ ||p|| `c` ```rust\nrust``` \
+             [link](https://example.com/a_(b\\)) [Ann](tg://user/?id=42) \
+             ![👍](tg://emoji?id=5368324170671202286)"
+        );
+    }
+
+    #[test]
+    fn nested() {
+        // <b>bold <i>both</i></b><i> italic</i>
+        let entities = [MessageEntity::bold(0, 9), MessageEntity::italic(5, 11)];
+        assert_eq!(
+            render_html("bold both italic", &entities),
+            "<b>bold <i>both</i></b><i> italic</i>"
+        );
+
+        // the shorter entity is inside even if it comes first
+        let entities = [MessageEntity::italic(0, 2), MessageEntity::bold(0, 4)];
+        assert_eq!(render_html("abcd", &entities), "<b><i>ab</i>cd</b>");
+
+        let entities = [MessageEntity::italic(0, 4), MessageEntity::underline(0, 2)];
+        assert_eq!(render_markdown("abcd", &entities), "_\r__ab__cd_");
+    }
+
+    #[test]
+    fn code_escaping() {
+        let entities = [MessageEntity::pre(None, 0, 9)];
+        assert_eq!(
+            render_markdown("a.b `c` \\", &entities),
+            "```\na.b \\`c\\` \\\\```"
+        );
+        assert_eq!(
+            render_html("<a> & b", &[MessageEntity::code(0, 7)]),
+            "<code>&lt;a&gt; &amp; b</code>"
+        );
+    }
+
+    #[test]
+    fn utf16_offsets() {
+        // 👍 takes 2 UTF-16 code units
+        let entities = [MessageEntity::bold(3, 1)];
+        assert_eq!(render_html("👍 x", &entities), "👍 <b>x</b>");
+    }
+
+    #[test]
+    fn ignores_detected_entities() {
+        let entities = [
+            MessageEntity::new(crate::types::MessageEntityKind::Url, 0, 11),
+            MessageEntity::bold(0, 0),
+        ];
+        assert_eq!(render_markdown("example.com", &entities), r"example\.com");
+    }
+}
@@ -0,0 +1,361 @@
+use std::borrow::Cow;
+
+use serde::Serialize;
+use url::Url;
+
+use crate::{
+    text::{render_html, render_markdown},
+    types::{MessageEntity, User, UserId},
+};
+
+/// Text with formatting, built piece by piece instead of escaped for a
+/// [`ParseMode`].
+///
+/// Anything can go into it as is, and it's sent with `entities`, so there is
+/// nothing to escape. Pieces nest by passing a `RichText` where text goes.
+/// Plain strings convert into it, so methods like
+/// [`Telegram::send_message`] take both.
+///
+/// ```no_run
+/// # fn main() -> anyhow::Result<()> {
+/// use tg_flows::{ChatId, RichText, Telegram};
+///
+/// let tele = Telegram::new(std::env::var("TOKEN")?);
+/// let name = "*not bold*";
+/// let text = RichText::new()
+///     .text("Hello, ")
+///     .bold(RichText::new().text(name).italic("!"))
+///     .text(" Run ")
+///     .code("cargo build");
+/// tele.send_message(ChatId(42), text)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`ParseMode`]: crate::types::ParseMode
+/// [`Telegram::send_message`]: crate::Telegram::send_message
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct RichText {
+    text: String,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    entities: Vec<MessageEntity>,
+
+    /// Length of `text` in UTF-16 code units.
+    #[serde(skip)]
+    len_utf16: usize,
+}
+
+impl RichText {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps text already formatted with `entities`, e.g. from a message.
+    pub fn from_parts<T>(text: T, entities: Vec<MessageEntity>) -> Self
+    where
+        T: Into<String>,
+    {
+        let text = text.into();
+        Self {
+            len_utf16: text.encode_utf16().count(),
+            text,
+            entities,
+        }
+    }
+
+    /// Appends plain text, or text with its own formatting.
+    #[must_use]
+    pub fn text<T>(mut self, text: T) -> Self
+    where
+        T: Into<RichText>,
+    {
+        self.append(text.into());
+        self
+    }
+
+    #[must_use]
+    pub fn bold<T>(self, text: T) -> Self
+    where
+        T: Into<RichText>,
+    {
+        self.wrap(text, MessageEntity::bold)
+    }
+
+    #[must_use]
+    pub fn italic<T>(self, text: T) -> Self
+    where
+        T: Into<RichText>,
+    {
+        self.wrap(text, MessageEntity::italic)
+    }
+
+    #[must_use]
+    pub fn underline<T>(self, text: T) -> Self
+    where
+        T: Into<RichText>,
+    {
+        self.wrap(text, MessageEntity::underline)
+    }
+
+    #[must_use]
+    pub fn strikethrough<T>(self, text: T) -> Self
+    where
+        T: Into<RichText>,
+    {
+        self.wrap(text, MessageEntity::strikethrough)
+    }
+
+    #[must_use]
+    pub fn spoiler<T>(self, text: T) -> Self
+    where
+        T: Into<RichText>,
+    {
+        self.wrap(text, MessageEntity::spoiler)
+    }
+
+    /// Appends monowidth text, which can't have other formatting.
+    #[must_use]
+    pub fn code<T>(self, code: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.wrap(code.into(), MessageEntity::code)
+    }
+
+    /// Appends a monowidth block, highlighted as `language` if any.
+    #[must_use]
+    pub fn pre<T>(self, code: T, language: Option<&str>) -> Self
+    where
+        T: Into<String>,
+    {
+        let language = language.map(str::to_owned);
+        self.wrap(code.into(), |offset, length| {
+            MessageEntity::pre(language, offset, length)
+        })
+    }
+
+    /// Appends `text` linking to `url`.
+    #[must_use]
+    pub fn link<T>(self, text: T, url: Url) -> Self
+    where
+        T: Into<RichText>,
+    {
+        self.wrap(text, |offset, length| {
+            MessageEntity::text_link(url, offset, length)
+        })
+    }
+
+    /// Appends the full name of `user`, mentioning them.
+    #[must_use]
+    pub fn mention(self, user: &User) -> Self {
+        let user = user.clone();
+        self.wrap(user.full_name(), |offset, length| {
+            MessageEntity::text_mention(user, offset, length)
+        })
+    }
+
+    /// Appends `text` mentioning the user with `user_id`, for when there is
+    /// no [`User`] at hand.
+    #[must_use]
+    pub fn mention_id<T>(self, text: T, user_id: UserId) -> Self
+    where
+        T: Into<RichText>,
+    {
+        self.wrap(text, |offset, length| {
+            MessageEntity::text_mention_id(user_id, offset, length)
+        })
+    }
+
+    /// Appends a custom emoji, where `emoji` is shown by clients which can't
+    /// show the custom one.
+    #[must_use]
+    pub fn custom_emoji<T, I>(self, emoji: T, custom_emoji_id: I) -> Self
+    where
+        T: Into<String>,
+        I: Into<String>,
+    {
+        let id = custom_emoji_id.into();
+        self.wrap(emoji.into(), |offset, length| {
+            MessageEntity::custom_emoji(id, offset, length)
+        })
+    }
+
+    /// Returns the text without formatting.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the formatting, with offsets in UTF-16 code units.
+    #[must_use]
+    pub fn entities(&self) -> &[MessageEntity] {
+        &self.entities
+    }
+
+    /// Returns the length of the text in UTF-16 code units, which Telegram
+    /// limits.
+    #[must_use]
+    pub fn len_utf16(&self) -> usize {
+        self.len_utf16
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    #[must_use]
+    pub fn into_parts(self) -> (String, Vec<MessageEntity>) {
+        (self.text, self.entities)
+    }
+
+    /// Returns the text as [`ParseMode::Html`] markup.
+    ///
+    /// [`ParseMode::Html`]: crate::types::ParseMode::Html
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        render_html(&self.text, &self.entities)
+    }
+
+    /// Returns the text as [`ParseMode::MarkdownV2`] markup.
+    ///
+    /// [`ParseMode::MarkdownV2`]: crate::types::ParseMode::MarkdownV2
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        render_markdown(&self.text, &self.entities)
+    }
+
+    fn append(&mut self, other: RichText) {
+        let offset = self.len_utf16;
+        self.entities
+            .extend(other.entities.into_iter().map(|entity| MessageEntity {
+                offset: entity.offset + offset,
+                ..entity
+            }));
+        self.text.push_str(&other.text);
+        self.len_utf16 += other.len_utf16;
+    }
+
+    /// Appends `text` formatted with the entity `new` creates, outside of the
+    /// formatting `text` has.
+    fn wrap<T, F>(mut self, text: T, new: F) -> Self
+    where
+        T: Into<RichText>,
+        F: FnOnce(usize, usize) -> MessageEntity,
+    {
+        let text = text.into();
+        if !text.is_empty() {
+            self.entities.push(new(self.len_utf16, text.len_utf16));
+        }
+        self.append(text);
+        self
+    }
+}
+
+impl From<&str> for RichText {
+    fn from(text: &str) -> Self {
+        Self::from_parts(text, Vec::new())
+    }
+}
+
+impl From<String> for RichText {
+    fn from(text: String) -> Self {
+        Self::from_parts(text, Vec::new())
+    }
+}
+
+impl From<&String> for RichText {
+    fn from(text: &String) -> Self {
+        Self::from_parts(text.as_str(), Vec::new())
+    }
+}
+
+impl From<Cow<'_, str>> for RichText {
+    fn from(text: Cow<'_, str>) -> Self {
+        Self::from_parts(text, Vec::new())
+    }
+}
+
+impl From<Box<str>> for RichText {
+    fn from(text: Box<str>) -> Self {
+        Self::from_parts(text, Vec::new())
+    }
+}
+
+impl From<char> for RichText {
+    fn from(c: char) -> Self {
+        Self::from_parts(c, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        text::RichText,
+        types::{MessageEntity, User, UserId},
+    };
+
+    #[test]
+    fn builds_entities() {
+        let user = User {
+            id: UserId(42),
+            is_bot: false,
+            first_name: "Ann".to_owned(),
+            last_name: Some("Lee".to_owned()),
+            username: None,
+            language_code: None,
+            is_premium: false,
+            added_to_attachment_menu: false,
+        };
+        let url: url::Url = "https://example.com/".parse().unwrap();
+
+        let text = RichText::new()
+            .text("👍 ")
+            .bold(RichText::new().text("a ").italic("b*"))
+            .text(" ")
+            .code("c")
+            .pre("fn main() {}", Some("rust"))
+            .link("d", url.clone())
+            .mention(&user)
+            .mention_id("e", UserId(7))
+            .custom_emoji("👍", "5368324170671202286")
+            .spoiler("")
+            .strikethrough("f")
+            .underline("g");
+
+        assert_eq!(text.as_str(), "👍 a b* cfn main() {}dAnn Leee👍fg");
+        assert_eq!(text.len_utf16(), 34);
+        assert_eq!(
+            text.entities(),
+            [
+                MessageEntity::bold(3, 4),
+                MessageEntity::italic(5, 2),
+                MessageEntity::code(8, 1),
+                MessageEntity::pre(Some("rust".to_owned()), 9, 12),
+                MessageEntity::text_link(url, 21, 1),
+                MessageEntity::text_mention(user, 22, 7),
+                MessageEntity::text_mention_id(UserId(7), 29, 1),
+                MessageEntity::custom_emoji("5368324170671202286".to_owned(), 30, 2),
+                MessageEntity::strikethrough(32, 1),
+                MessageEntity::underline(33, 1),
+            ]
+        );
+        assert_eq!(RichText::new().bold("a_b").to_markdown(), r"*a\_b*");
+    }
+
+    #[test]
+    fn converts_strings() {
+        let text = RichText::new()
+            .text(std::borrow::Cow::Borrowed("a"))
+            .bold(Box::<str>::from("b"))
+            .italic('👍');
+
+        assert_eq!(text.as_str(), "ab👍");
+        assert_eq!(
+            text.entities(),
+            [MessageEntity::bold(1, 1), MessageEntity::italic(2, 2)]
+        );
+    }
+}
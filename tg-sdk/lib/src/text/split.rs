@@ -0,0 +1,228 @@
+use std::ops::Range;
+
+use crate::{
+    text::RichText,
+    types::{MessageEntity, MessageEntityKind},
+};
+
+/// The most UTF-16 code units Telegram accepts in the text of a message.
+pub const MESSAGE_LENGTH_LIMIT: usize = 4096;
+
+/// The most UTF-16 code units Telegram accepts in a caption.
+pub const CAPTION_LENGTH_LIMIT: usize = 1024;
+
+/// How good a place to split text is, best first.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Break {
+    Paragraph,
+    Line,
+    Sentence,
+    Word,
+    Anywhere,
+}
+
+impl RichText {
+    /// Splits the text into parts of at most `limit` UTF-16 code units, e.g.
+    /// [`MESSAGE_LENGTH_LIMIT`], keeping the formatting of each part.
+    ///
+    /// Parts end at paragraphs, lines, sentences or words, in that order of
+    /// preference, and only in a `pre` block or a word if it doesn't fit
+    /// otherwise. Entities spanning parts are closed at the end of one and
+    /// reopened in the next. Whitespace at the end of parts is dropped.
+    ///
+    /// ```
+    /// use tg_flows::RichText;
+    ///
+    /// let text = RichText::new().bold("First sentence. Second one.\n\nNext paragraph.");
+    /// let parts = text.split(30);
+    /// assert_eq!(parts[0], RichText::new().bold("First sentence. Second one."));
+    /// assert_eq!(parts[1], RichText::new().bold("Next paragraph."));
+    /// ```
+    #[must_use]
+    pub fn split(&self, limit: usize) -> Vec<RichText> {
+        // Room for a surrogate pair, which can't be split.
+        let limit = limit.max(2);
+
+        // Char boundaries as UTF-16 offset and byte offset.
+        let mut bounds = Vec::with_capacity(self.as_str().len() + 1);
+        let mut len_utf16 = 0;
+        for (i, c) in self.as_str().char_indices() {
+            bounds.push((len_utf16, i));
+            len_utf16 += c.len_utf16();
+        }
+        bounds.push((len_utf16, self.as_str().len()));
+
+        let pre_blocks: Vec<_> = self
+            .entities()
+            .iter()
+            .filter(|e| matches!(e.kind, MessageEntityKind::Pre { .. }))
+            .map(|e| e.offset..e.offset + e.length)
+            .collect();
+
+        let mut parts = Vec::new();
+        let mut start = 0;
+        while start + 1 < bounds.len() {
+            let max = bounds[start].0 + limit;
+            let end = bounds.partition_point(|&(offset, _)| offset <= max) - 1;
+            let cut = match end + 1 == bounds.len() {
+                true => end,
+                false => self.find_cut(&bounds, start, end, &pre_blocks),
+            };
+
+            parts.extend(self.slice(bounds[start], bounds[cut]));
+            start = cut;
+        }
+
+        parts
+    }
+
+    /// Returns the index of the best bound to split at in `start + 1..=end`.
+    fn find_cut(
+        &self,
+        bounds: &[(usize, usize)],
+        start: usize,
+        end: usize,
+        pre_blocks: &[Range<usize>],
+    ) -> usize {
+        let text = self.as_str();
+        let break_at = |i: usize| {
+            let before = &text[..bounds[i].1];
+            if before.ends_with("\n\n") {
+                Break::Paragraph
+            } else if before.ends_with('\n') {
+                Break::Line
+            } else if [". ", "! ", "? "].iter().any(|end| before.ends_with(end)) {
+                Break::Sentence
+            } else if before.ends_with(char::is_whitespace) {
+                Break::Word
+            } else {
+                Break::Anywhere
+            }
+        };
+        let in_pre = |i: usize| {
+            let offset = bounds[i].0;
+            pre_blocks
+                .iter()
+                .any(|pre| pre.start < offset && offset < pre.end)
+        };
+
+        // Paragraphs, lines and sentences only count past the middle, so
+        // that parts aren't much shorter than they could be.
+        let middle = (bounds[start].0 + bounds[end].0) / 2;
+        for allow_pre in [false, true] {
+            for best in [Break::Paragraph, Break::Line, Break::Sentence, Break::Word] {
+                let found = (start + 1..=end).rev().find(|&i| {
+                    (best == Break::Word || bounds[i].0 > middle)
+                        && break_at(i) <= best
+                        && (allow_pre || !in_pre(i))
+                });
+                if let Some(i) = found {
+                    return i;
+                }
+            }
+        }
+
+        end
+    }
+
+    /// Returns the part between two bounds, without whitespace at its end.
+    fn slice(&self, (start, from): (usize, usize), (_, to): (usize, usize)) -> Option<RichText> {
+        let text = self.as_str()[from..to].trim_end();
+        if text.is_empty() {
+            return None;
+        }
+        let end = start + text.encode_utf16().count();
+
+        let entities = self
+            .entities()
+            .iter()
+            .filter_map(|entity| {
+                let offset = entity.offset.max(start);
+                let entity_end = (entity.offset + entity.length).min(end);
+                (offset < entity_end).then(|| MessageEntity {
+                    offset: offset - start,
+                    length: entity_end - offset,
+                    ..entity.clone()
+                })
+            })
+            .collect();
+
+        Some(RichText::from_parts(text, entities))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        text::{RichText, MESSAGE_LENGTH_LIMIT},
+        types::MessageEntity,
+    };
+
+    fn texts(parts: &[RichText]) -> Vec<&str> {
+        parts.iter().map(RichText::as_str).collect()
+    }
+
+    #[test]
+    fn short() {
+        let text = RichText::from("hi");
+        assert_eq!(text.split(MESSAGE_LENGTH_LIMIT), [text]);
+        assert!(RichText::new().split(10).is_empty());
+    }
+
+    #[test]
+    fn prefers_paragraphs_lines_sentences() {
+        let text = RichText::from("aaaa aaaa.\n\nbbbb\nbbbb. cccc cccc");
+        assert_eq!(
+            texts(&text.split(16)),
+            ["aaaa aaaa.", "bbbb\nbbbb.", "cccc cccc"]
+        );
+        assert_eq!(
+            texts(&text.split(22)),
+            ["aaaa aaaa.", "bbbb\nbbbb. cccc cccc"]
+        );
+        assert_eq!(
+            texts(&RichText::from("aaaa bbbb cc").split(7)),
+            ["aaaa", "bbbb cc"]
+        );
+        assert_eq!(
+            texts(&RichText::from("aaaaaaaa").split(3)),
+            ["aaa", "aaa", "aa"]
+        );
+    }
+
+    #[test]
+    fn keeps_surrogate_pairs() {
+        // each 👍 is 2 UTF-16 code units
+        let parts = RichText::from("👍👍👍").split(3);
+        assert_eq!(texts(&parts), ["👍", "👍", "👍"]);
+        assert!(parts.iter().all(|part| part.len_utf16() == 2));
+    }
+
+    #[test]
+    fn reopens_entities() {
+        let text = RichText::new().text("ab ").bold("cd ef").text(" gh");
+        let parts = text.split(6);
+        assert_eq!(texts(&parts), ["ab cd", "ef gh"]);
+        assert_eq!(parts[0].entities(), [MessageEntity::bold(3, 2)]);
+        assert_eq!(parts[1].entities(), [MessageEntity::bold(0, 2)]);
+    }
+
+    #[test]
+    fn avoids_pre_blocks() {
+        let text = RichText::new()
+            .text("intro text\n")
+            .pre("line 1\nline 2\n", Some("text"))
+            .text("outro");
+        let parts = text.split(20);
+        assert_eq!(texts(&parts), ["intro text", "line 1\nline 2\noutro"]);
+        assert_eq!(
+            parts[1].entities(),
+            [MessageEntity::pre(Some("text".to_owned()), 0, 14)]
+        );
+
+        // a block longer than the limit is split at its lines
+        let parts = RichText::new().pre("line 1\nline 2\n", None).split(10);
+        assert_eq!(texts(&parts), ["line 1", "line 2"]);
+        assert_eq!(parts[1].entities(), [MessageEntity::pre(None, 0, 6)]);
+    }
+}
@@ -77,6 +77,7 @@ pub use message::*;
 pub use message_auto_delete_timer_changed::*;
 pub use message_entity::*;
 pub use message_id::*;
+pub use new_invoice::*;
 pub use order_info::*;
 pub use parse_mode::*;
 pub use passport_data::*;
@@ -171,6 +172,7 @@ mod message;
 mod message_auto_delete_timer_changed;
 mod message_entity;
 mod message_id;
+mod new_invoice;
 mod order_info;
 mod parse_mode;
 mod photo_size;
@@ -238,11 +240,13 @@ mod passport_data;
 mod passport_element_error;
 mod passport_file;
 
-pub use non_telegram_types::{country_code::*, currency::*, until_date::*};
+pub use non_telegram_types::{country_code::*, currency::*, deep_link::*, money::*, until_date::*};
 mod non_telegram_types {
     pub(super) mod country_code;
     pub(super) mod currency;
+    pub(super) mod deep_link;
     pub(crate) mod mime;
+    pub(super) mod money;
     pub(super) mod until_date;
 }
 
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 /// Represents the rights of an administrator in a chat.
-#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+///
+/// The [`Default`] is no rights at all.
+#[serde_with_macros::skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct ChatAdministratorRights {
     /// `true`, if the user's presence in the chat is hidden
     pub is_anonymous: bool,
@@ -20,7 +20,7 @@ pub struct EncryptedCredentials {
     ///
     /// [`EncryptedPassportElement`]:
     /// crate::types::EncryptedPassportElement
-    pub data: String, // TODO: check base64 type
+    pub data: String,
 
     /// Base64-encoded data hash for data authentication.
     pub hash: String,
@@ -20,7 +20,7 @@ pub struct EncryptedPassportElement {
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+#[serde(tag = "type", rename_all = "snake_case")]
 #[allow(clippy::large_enum_variant)]
 pub enum EncryptedPassportElementKind {
     PersonalDetails(EncryptedPassportElementPersonalDetails),
@@ -33,7 +33,7 @@ pub enum EncryptedPassportElementKind {
     BankStatement(EncryptedPassportElementBankStatement),
     RentalAgreement(EncryptedPassportElementRentalAgreement),
     PassportRegistration(EncryptedPassportElementPassportRegistration),
-    EncryptedPassportElement(EncryptedPassportElementTemporaryRegistration),
+    TemporaryRegistration(EncryptedPassportElementTemporaryRegistration),
     PhoneNumber(EncryptedPassportElementPhoneNumber),
     Email(EncryptedPassportElementEmail),
 }
@@ -81,7 +81,7 @@ pub struct EncryptedPassportElementPassport {
     ///
     /// [`EncryptedCredentials`]:
     /// crate::types::EncryptedCredentials
-    pub selfie: PassportFile,
+    pub selfie: Option<PassportFile>,
 
     /// Array of encrypted files with translated versions of documents
     /// provided by the user. Available if requested for `passport`,
@@ -134,7 +134,7 @@ pub struct EncryptedPassportElementDriverLicense {
     ///
     /// [`EncryptedCredentials`]:
     /// crate::types::EncryptedCredentials
-    pub selfie: PassportFile,
+    pub selfie: Option<PassportFile>,
 
     /// Array of encrypted files with translated versions of documents
     /// provided by the user. Available if requested for `passport`,
@@ -187,7 +187,7 @@ pub struct EncryptedPassportElementIdentityCard {
     ///
     /// [`EncryptedCredentials`]:
     /// crate::types::EncryptedCredentials
-    pub selfie: PassportFile,
+    pub selfie: Option<PassportFile>,
 
     /// Array of encrypted files with translated versions of documents
     /// provided by the user. Available if requested for `passport`,
@@ -231,7 +231,7 @@ pub struct EncryptedPassportElementInternalPassport {
     ///
     /// [`EncryptedCredentials`]:
     /// crate::types::EncryptedCredentials
-    pub selfie: PassportFile,
+    pub selfie: Option<PassportFile>,
 
     /// Array of encrypted files with translated versions of documents
     /// provided by the user. Available if requested for `passport`,
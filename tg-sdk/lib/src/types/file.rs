@@ -4,15 +4,15 @@ use serde::{Deserialize, Serialize};
 
 /// This object represents a file ready to be downloaded.
 ///
-/// The file can be downloaded via the [`Bot::download_file(file_path, dst)`]
-/// method. It is guaranteed that the path from [`GetFile`] will be valid for at
-/// least 1 hour. When the path expires, a new one can be requested by calling
-/// [`GetFile`].
+/// The file can be downloaded via the [`Telegram::download_file`] method. It
+/// is guaranteed that the path from [`Telegram::get_file`] will be valid for
+/// at least 1 hour. When the path expires, a new one can be requested by
+/// calling [`Telegram::get_file`].
 ///
 /// [The official docs](https://core.telegram.org/bots/api#file).
 ///
-/// [`GetFile`]: crate::payloads::GetFile
-/// [`Bot::download_file(file_path, dst)`]: crate::net::Download::download_file
+/// [`Telegram::download_file`]: crate::Telegram::download_file
+/// [`Telegram::get_file`]: crate::Telegram::get_file
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct File {
     /// Metadata of the file.
@@ -22,9 +22,9 @@ pub struct File {
     #[serde(flatten)]
     pub meta: FileMeta,
 
-    /// File path. Use [`Bot::download_file(file_path, dst)`] to get the file.
+    /// File path. Use [`Telegram::download_file`] to get the file.
     ///
-    /// [`Bot::download_file(file_path, dst)`]: crate::net::Download::download_file
+    /// [`Telegram::download_file`]: crate::Telegram::download_file
     #[serde(rename = "file_path")]
     pub path: String,
 }
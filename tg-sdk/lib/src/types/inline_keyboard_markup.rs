@@ -45,6 +45,25 @@ impl InlineKeyboardMarkup {
         }
     }
 
+    /// Lays `buttons` out in rows of `columns` buttons, the last row having
+    /// the rest.
+    ///
+    /// ```
+    /// use tg_flows::{InlineKeyboardButton, InlineKeyboardMarkup};
+    ///
+    /// let buttons = (1..=5).map(|n| InlineKeyboardButton::callback(n.to_string(), n.to_string()));
+    /// let markup = InlineKeyboardMarkup::grid(buttons, 2);
+    /// let rows: Vec<_> = markup.inline_keyboard.iter().map(Vec::len).collect();
+    /// assert_eq!(rows, [2, 2, 1]);
+    /// ```
+    pub fn grid<I>(buttons: I, columns: usize) -> Self
+    where
+        I: IntoIterator<Item = InlineKeyboardButton>,
+    {
+        let buttons: Vec<_> = buttons.into_iter().collect();
+        Self::new(buttons.chunks(columns.max(1)).map(<[_]>::to_vec))
+    }
+
     pub fn inline_keyboard<I>(mut self, val: I) -> Self
     where
         I: IntoIterator,
@@ -120,4 +139,19 @@ mod tests {
 
         assert_eq!(markup, expected);
     }
+
+    #[test]
+    fn grid() {
+        let buttons: Vec<_> =
+            (1..=4).map(|n| InlineKeyboardButton::url(n.to_string(), url(n))).collect();
+
+        let markup = InlineKeyboardMarkup::grid(buttons.clone(), 3);
+        let expected = InlineKeyboardMarkup {
+            inline_keyboard: vec![buttons[..3].to_vec(), buttons[3..].to_vec()],
+        };
+        assert_eq!(markup, expected);
+
+        assert_eq!(InlineKeyboardMarkup::grid(buttons.clone(), 0).inline_keyboard.len(), 4);
+        assert!(InlineKeyboardMarkup::grid([], 3).inline_keyboard.is_empty());
+    }
 }
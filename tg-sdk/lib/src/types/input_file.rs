@@ -151,9 +151,28 @@ impl InputFile {
         }
     }
 
+    /// Returns the contents to upload of a file that [needs
+    /// attaching](Self::needs_attach).
+    ///
+    /// Files created with [`InputFile::read`] can't be uploaded yet.
+    pub(crate) fn contents(&self) -> io::Result<bytes::Bytes> {
+        match &self.inner {
+            Bytes(bytes) => Ok(bytes.clone()),
+            File(path) => std::fs::read(path).map(Into::into),
+            Read(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "uploading files created with `InputFile::read` is not supported",
+            )),
+            Url(_) | FileId(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "urls and file ids are sent as they are, not uploaded",
+            )),
+        }
+    }
+
     /// Takes the file name or tries to guess it based on file name in the path
     /// if `File.0`. Returns an empty string if couldn't guess.
-    fn _take_or_guess_filename(&mut self) -> Cow<'static, str> {
+    pub(crate) fn take_or_guess_filename(&mut self) -> Cow<'static, str> {
         self.file_name.take().unwrap_or_else(|| match &self.inner {
             File(path_to_file) => match path_to_file.file_name() {
                 Some(name) => Cow::Owned(name.to_string_lossy().into_owned()),
@@ -2,7 +2,7 @@ use std::ops::Deref;
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::User;
+use crate::types::{DeepLink, PayloadTooLongError, User};
 
 /// Returned only in [`GetMe`].
 ///
@@ -42,6 +42,14 @@ impl Me {
     pub fn tme_url(&self) -> url::Url {
         format!("https://t.me/{}", self.username()).parse().unwrap()
     }
+
+    /// Returns a [`DeepLink`] to this bot, carrying `payload`.
+    pub fn deep_link<P>(&self, link: &DeepLink, payload: P) -> Result<url::Url, PayloadTooLongError>
+    where
+        P: AsRef<[u8]>,
+    {
+        link.url(self.username(), payload)
+    }
 }
 
 impl Deref for Me {
@@ -54,7 +62,7 @@ impl Deref for Me {
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{Me, User, UserId};
+    use crate::types::{DeepLink, Me, User, UserId};
 
     #[test]
     fn convenience_methods_work() {
@@ -77,5 +85,9 @@ mod tests {
         assert_eq!(me.username(), "SomethingSomethingBot");
         assert_eq!(me.mention(), "@SomethingSomethingBot");
         assert_eq!(me.tme_url(), "https://t.me/SomethingSomethingBot".parse().unwrap());
+        assert_eq!(
+            me.deep_link(&DeepLink::StartGroup, "admin").unwrap(),
+            "https://t.me/SomethingSomethingBot?startgroup=YWRtaW4".parse().unwrap()
+        );
     }
 }
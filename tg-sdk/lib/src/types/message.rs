@@ -5,9 +5,9 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::types::{
-    Animation, Audio, BareChatId, Chat, ChatId, Contact, Dice, Document, ForumTopicClosed,
-    ForumTopicCreated, ForumTopicEdited, ForumTopicReopened, Game, GeneralForumTopicHidden,
-    GeneralForumTopicUnhidden, InlineKeyboardMarkup, Invoice, Location,
+    Animation, Audio, BareChatId, Chat, ChatId, Contact, Dice, Document, EntityOffsetError,
+    ForumTopicClosed, ForumTopicCreated, ForumTopicEdited, ForumTopicReopened, Game,
+    GeneralForumTopicHidden, GeneralForumTopicUnhidden, InlineKeyboardMarkup, Invoice, Location,
     MessageAutoDeleteTimerChanged, MessageEntity, MessageEntityRef, MessageId, PassportData,
     PhotoSize, Poll, ProximityAlertTriggered, Sticker, SuccessfulPayment, True, User, Venue, Video,
     VideoChatEnded, VideoChatParticipantsInvited, VideoChatScheduled, VideoChatStarted, VideoNote,
@@ -1384,27 +1384,118 @@ impl Message {
     ///
     /// This function returns `Some(entities)` for **text messages** and
     /// `None` for all other kinds of messages (including photos with
-    /// captions).
+    /// captions), or if the entities don't fit the text, which only a forged
+    /// update can have.
     ///
-    /// See also: [`parse_caption_entities`].
+    /// See also: [`parse_caption_entities`], [`try_parse_entities`].
     ///
     /// [`parse_caption_entities`]: Message::parse_caption_entities
+    /// [`try_parse_entities`]: Message::try_parse_entities
     #[must_use]
     pub fn parse_entities(&self) -> Option<Vec<MessageEntityRef<'_>>> {
-        self.text().zip(self.entities()).map(|(t, e)| MessageEntityRef::parse(t, e))
+        self.try_parse_entities()?.ok()
+    }
+
+    /// Returns message entities that represent text formatting, like
+    /// [`parse_entities`], or why they don't fit the text.
+    ///
+    /// [`parse_entities`]: Message::parse_entities
+    #[must_use]
+    pub fn try_parse_entities(
+        &self,
+    ) -> Option<Result<Vec<MessageEntityRef<'_>>, EntityOffsetError>> {
+        let (text, entities) = self.text().zip(self.entities())?;
+        Some(MessageEntityRef::try_parse(text, entities))
     }
 
     /// Returns message entities that represent text formatting.
     ///
     /// This function returns `Some(entities)` for **media messages** and
-    /// `None` for all other kinds of messages (including text messages).
+    /// `None` for all other kinds of messages (including text messages), or
+    /// if the entities don't fit the caption, which only a forged update can
+    /// have.
     ///
-    /// See also: [`parse_entities`].
+    /// See also: [`parse_entities`], [`try_parse_caption_entities`].
     ///
     /// [`parse_entities`]: Message::parse_entities
+    /// [`try_parse_caption_entities`]: Message::try_parse_caption_entities
     #[must_use]
     pub fn parse_caption_entities(&self) -> Option<Vec<MessageEntityRef<'_>>> {
-        self.caption().zip(self.caption_entities()).map(|(t, e)| MessageEntityRef::parse(t, e))
+        self.try_parse_caption_entities()?.ok()
+    }
+
+    /// Returns message entities that represent caption formatting, like
+    /// [`parse_caption_entities`], or why they don't fit the caption.
+    ///
+    /// [`parse_caption_entities`]: Message::parse_caption_entities
+    #[must_use]
+    pub fn try_parse_caption_entities(
+        &self,
+    ) -> Option<Result<Vec<MessageEntityRef<'_>>, EntityOffsetError>> {
+        let (caption, entities) = self.caption().zip(self.caption_entities())?;
+        Some(MessageEntityRef::try_parse(caption, entities))
+    }
+
+    /// Returns the text, or the caption, of this message as
+    /// [`ParseMode::Html`] markup which keeps its formatting.
+    ///
+    /// This is useful to re-send or quote messages of users.
+    ///
+    /// See also: [`markdown_text`].
+    ///
+    /// [`ParseMode::Html`]: crate::types::ParseMode::Html
+    /// [`markdown_text`]: Message::markdown_text
+    #[must_use]
+    pub fn html_text(&self) -> Option<String> {
+        self.formatted_text().map(|(t, e)| crate::text::render_html(t, e))
+    }
+
+    /// Returns the text, or the caption, of this message as
+    /// [`ParseMode::MarkdownV2`] markup which keeps its formatting.
+    ///
+    /// See also: [`html_text`].
+    ///
+    /// [`ParseMode::MarkdownV2`]: crate::types::ParseMode::MarkdownV2
+    /// [`html_text`]: Message::html_text
+    #[must_use]
+    pub fn markdown_text(&self) -> Option<String> {
+        self.formatted_text().map(|(t, e)| crate::text::render_markdown(t, e))
+    }
+
+    fn formatted_text(&self) -> Option<(&str, &[MessageEntity])> {
+        match self.text() {
+            Some(text) => Some((text, self.entities().unwrap_or_default())),
+            None => self.caption().map(|c| (c, self.caption_entities().unwrap_or_default())),
+        }
+    }
+
+    /// Returns the parameter of a `/start` command, which is how a bot gets
+    /// the payload of a [`DeepLink`], as is.
+    ///
+    /// Returns `None` for other messages and `/start` without a parameter.
+    ///
+    /// See also: [`start_payload`].
+    ///
+    /// [`DeepLink`]: crate::types::DeepLink
+    /// [`start_payload`]: Message::start_payload
+    #[must_use]
+    pub fn start_parameter(&self) -> Option<&str> {
+        let (command, parameter) = self.text()?.split_once(' ')?;
+        let command = command.split_once('@').map_or(command, |(command, _bot)| command);
+        let parameter = parameter.trim();
+        (command == "/start" && !parameter.is_empty()).then_some(parameter)
+    }
+
+    /// Returns the payload of the [`DeepLink`] which started the bot, decoded.
+    ///
+    /// Returns `None` if there is no [`start_parameter`], or it isn't
+    /// base64url.
+    ///
+    /// [`DeepLink`]: crate::types::DeepLink
+    /// [`start_parameter`]: Message::start_parameter
+    #[must_use]
+    pub fn start_payload(&self) -> Option<Vec<u8>> {
+        crate::types::decode_start_payload(self.start_parameter()?)
     }
 
     /// Returns all users that are "contained" in this `Message` structure.
@@ -1889,6 +1980,23 @@ mod tests {
         assert_eq!(entities[0].kind().clone(), MessageEntityKind::Url);
     }
 
+    #[test]
+    fn try_parse_entities() {
+        let message: Message = serde_json::from_value(serde_json::json!({
+            "message_id": 1,
+            "date": 0,
+            "chat": { "id": 42, "type": "private", "first_name": "Ann" },
+            "text": "hi",
+            "entities": [{ "type": "bold", "offset": 1, "length": 5 }],
+        }))
+        .unwrap();
+
+        let error = message.try_parse_entities().unwrap().unwrap_err();
+        assert_eq!((error.index, error.kind), (0, EntityOffsetErrorKind::OutOfBounds));
+        assert!(message.parse_entities().is_none());
+        assert!(message.try_parse_caption_entities().is_none());
+    }
+
     #[test]
     fn topic_created() {
         let json = r#"{
@@ -1920,4 +2028,37 @@ mod tests {
 
         let _: Message = serde_json::from_str(json).unwrap();
     }
+
+    #[test]
+    fn formatted_text() {
+        let json = r#"{"chat":{"id":1,"type":"private","first_name":"Ann"},"date":1675229140,"message_id":5,"text":"hi *there*","entities":[{"type":"bold","offset":3,"length":7}]}"#;
+        let message: Message = serde_json::from_str(json).unwrap();
+        assert_eq!(message.html_text().as_deref(), Some("hi <b>*there*</b>"));
+        assert_eq!(message.markdown_text().as_deref(), Some(r"hi *\*there\**"));
+
+        let json = r#"{"chat":{"id":1,"type":"private","first_name":"Ann"},"date":1675229140,"message_id":6,"photo":[],"caption":"a & b","caption_entities":[{"type":"italic","offset":4,"length":1}]}"#;
+        let message: Message = serde_json::from_str(json).unwrap();
+        assert_eq!(message.html_text().as_deref(), Some("a &amp; <i>b</i>"));
+    }
+
+    #[test]
+    fn start_payload() {
+        let message = |text: &str| -> Message {
+            serde_json::from_value(serde_json::json!({
+                "chat": { "id": 1, "type": "private", "first_name": "Ann" },
+                "date": 1675229140,
+                "message_id": 5,
+                "text": text,
+            }))
+            .unwrap()
+        };
+
+        assert_eq!(message("/start cmVmLTQy").start_payload().as_deref(), Some(&b"ref-42"[..]));
+        assert_eq!(message("/start@ExampleBot AQI").start_payload(), Some(vec![1, 2]));
+        assert_eq!(message("/start not+base64").start_parameter(), Some("not+base64"));
+        assert_eq!(message("/start not+base64").start_payload(), None);
+        assert_eq!(message("/start").start_parameter(), None);
+        assert_eq!(message("/started cmVmLTQy").start_parameter(), None);
+        assert_eq!(message("/help cmVmLTQy").start_parameter(), None);
+    }
 }
@@ -1,4 +1,4 @@
-use std::{cmp, ops::Range};
+use std::{cmp, fmt, ops::Range};
 
 use serde::{Deserialize, Serialize};
 
@@ -237,65 +237,108 @@ impl<'a> MessageEntityRef<'a> {
     }
 
     /// Parses telegram [`MessageEntity`]s converting offsets to UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// If an entity doesn't fit `text`, which a forged update can have, see
+    /// [`MessageEntityRef::try_parse`].
     #[must_use]
     pub fn parse(text: &'a str, entities: &'a [MessageEntity]) -> Vec<Self> {
+        Self::try_parse(text, entities).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Parses telegram [`MessageEntity`]s converting offsets to UTF-8, failing
+    /// on the first entity which is outside of `text` or splits a character.
+    pub fn try_parse(
+        text: &'a str,
+        entities: &'a [MessageEntity],
+    ) -> Result<Vec<Self>, EntityOffsetError> {
+        let error = |index: usize, kind| EntityOffsetError {
+            index,
+            offset: entities[index].offset,
+            length: entities[index].length,
+            kind,
+        };
+
         // This creates entities with **wrong** offsets (UTF-16) that we later patch.
-        let mut entities: Vec<_> = entities
+        let mut parsed: Vec<_> = entities
             .iter()
             .map(|e| Self {
                 message: text,
-                range: e.offset..e.offset + e.length,
+                range: e.offset..e.offset.saturating_add(e.length),
                 kind: &e.kind,
             })
             .collect();
 
-        // Convert offsets
-
-        // References to all offsets that need patching
-        let mut offsets: Vec<&mut usize> = entities
+        let mut offsets: Vec<(usize, &mut usize)> = parsed
             .iter_mut()
-            .flat_map(
-                |Self {
-                     range: Range { start, end },
-                     ..
-                 }| [start, end],
-            )
+            .enumerate()
+            .flat_map(|(i, e)| [(i, &mut e.range.start), (i, &mut e.range.end)])
             .collect();
+        offsets.sort_unstable_by_key(|(_, offset)| cmp::Reverse(**offset));
+
+        let (mut len_utf8, mut len_utf16) = (0, 0);
+        let mut chars = text.chars();
+        while let Some((index, offset)) = offsets.pop() {
+            while len_utf16 < *offset {
+                let Some(c) = chars.next() else {
+                    return Err(error(index, EntityOffsetErrorKind::OutOfBounds));
+                };
+                len_utf8 += c.len_utf8();
+                len_utf16 += c.len_utf16();
+            }
+            if len_utf16 != *offset {
+                return Err(error(index, EntityOffsetErrorKind::SplitsCharacter));
+            }
+
+            *offset = len_utf8;
+        }
 
-        // Sort in decreasing order, so the smallest elements are at the end and can be
-        // removed more easily
-        offsets.sort_unstable_by_key(|&&mut offset| cmp::Reverse(offset));
-
-        let _ = text
-            .chars()
-            .chain(['\0']) // this is needed to process offset pointing at the end of the string
-            .try_fold((0, 0), |(len_utf8, len_utf16), c| {
-                // Stop if there are no more offsets to patch
-                if offsets.is_empty() {
-                    return None;
-                }
+        Ok(parsed)
+    }
+}
 
-                // Patch all offsets that can be patched
-                while offsets
-                    .last()
-                    .map(|&&mut offset| offset <= len_utf16)
-                    .unwrap_or(false)
-                {
-                    let offset = offsets.pop().unwrap();
-                    assert_eq!(*offset, len_utf16, "Invalid utf-16 offset");
-
-                    // Patch the offset to be UTF-8
-                    *offset = len_utf8;
-                }
+/// Why [`MessageEntityRef::try_parse`] failed.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EntityOffsetError {
+    /// Index of the entity in the list.
+    pub index: usize,
+
+    /// Offset of the entity in UTF-16 code units.
+    pub offset: usize,
+
+    /// Length of the entity in UTF-16 code units.
+    pub length: usize,
+
+    pub kind: EntityOffsetErrorKind,
+}
 
-                // Update "running" length
-                Some((len_utf8 + c.len_utf8(), len_utf16 + c.len_utf16()))
-            });
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EntityOffsetErrorKind {
+    /// The entity ends after the text.
+    OutOfBounds,
+
+    /// The entity starts or ends between the two UTF-16 code units of a
+    /// surrogate pair.
+    SplitsCharacter,
+}
 
-        entities
+impl fmt::Display for EntityOffsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problem = match self.kind {
+            EntityOffsetErrorKind::OutOfBounds => "is outside of the text",
+            EntityOffsetErrorKind::SplitsCharacter => "splits a character",
+        };
+        write!(
+            f,
+            "entity #{} at UTF-16 offset {} of length {} {problem}",
+            self.index, self.offset, self.length
+        )
     }
 }
 
+impl std::error::Error for EntityOffsetError {}
+
 #[serde_with_macros::skip_serializing_none]
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -324,6 +367,7 @@ pub enum MessageEntityKind {
 mod tests {
     use super::*;
     use cool_asserts::assert_matches;
+    use proptest::{collection::vec, prelude::*};
     use MessageEntity;
     use MessageEntityKind::*;
 
@@ -505,4 +549,56 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn try_parse_errors() {
+        let entities = [MessageEntity::bold(0, 2), MessageEntity::italic(1, 3)];
+        let error = MessageEntityRef::try_parse("👍 ok", &entities).unwrap_err();
+        assert_eq!(error.index, 1);
+        assert_eq!(error.kind, EntityOffsetErrorKind::SplitsCharacter);
+
+        let error = MessageEntityRef::try_parse("ok", &entities).unwrap_err();
+        assert_eq!(error.index, 1);
+        assert_eq!(error.kind, EntityOffsetErrorKind::OutOfBounds);
+        assert_eq!(
+            error.to_string(),
+            "entity #1 at UTF-16 offset 1 of length 3 is outside of the text"
+        );
+
+        let forged = [MessageEntity::bold(1, usize::MAX)];
+        assert!(MessageEntityRef::try_parse("ok", &forged).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn try_parse_matches_utf16(
+            text in any::<String>(),
+            ranges in vec((0..40_usize, 0..40_usize), 0..8),
+        ) {
+            let units: Vec<u16> = text.encode_utf16().collect();
+            let fits = |offset: usize| {
+                offset <= units.len() && String::from_utf16(&units[..offset]).is_ok()
+            };
+            let entities: Vec<_> = ranges
+                .iter()
+                .map(|&(offset, length)| MessageEntity::bold(offset, length))
+                .collect();
+
+            match MessageEntityRef::try_parse(&text, &entities) {
+                Ok(parsed) => {
+                    for (entity, parsed) in entities.iter().zip(parsed) {
+                        let range = entity.offset..entity.offset + entity.length;
+                        prop_assert_eq!(parsed.text(), String::from_utf16(&units[range]).unwrap());
+                    }
+                }
+                Err(error) => {
+                    let bad = &entities[error.index];
+                    prop_assert!(!fits(bad.offset) || !fits(bad.offset + bad.length));
+                }
+            }
+
+            let all_fit = entities.iter().all(|e| fits(e.offset) && fits(e.offset + e.length));
+            prop_assert_eq!(MessageEntityRef::try_parse(&text, &entities).is_ok(), all_fit);
+        }
+    }
 }
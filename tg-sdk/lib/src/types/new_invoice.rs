@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::types::{Currency, LabeledPrice};
+
+/// An invoice to send with [`Telegram::send_invoice`] or to link to with
+/// [`Telegram::create_invoice_link`].
+///
+/// Amounts are integers in the smallest units of the currency, e.g. `1099`
+/// for `US$ 10.99`.
+///
+/// [The official docs](https://core.telegram.org/bots/api#sendinvoice).
+///
+/// [`Telegram::send_invoice`]: crate::Telegram::send_invoice
+/// [`Telegram::create_invoice_link`]: crate::Telegram::create_invoice_link
+#[serde_with_macros::skip_serializing_none]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct NewInvoice {
+    /// Product name, 1-32 characters.
+    pub title: String,
+
+    /// Product description, 1-255 characters.
+    pub description: String,
+
+    /// Bot-defined invoice payload, 1-128 bytes. This will not be displayed
+    /// to the user, use for your internal processes.
+    pub payload: String,
+
+    /// Payment provider token, obtained via [@BotFather]. Not needed for
+    /// payments in [Telegram Stars](Currency::XTR).
+    ///
+    /// [@BotFather]: https://t.me/botfather
+    pub provider_token: Option<String>,
+
+    pub currency: Currency,
+
+    /// Price breakdown (e.g. product price, tax, discount, delivery cost,
+    /// delivery tax, bonus, etc.).
+    pub prices: Vec<LabeledPrice>,
+
+    /// The maximum accepted amount for tips.
+    pub max_tip_amount: Option<i32>,
+
+    /// At most 4 suggested amounts of tips, positive, in increasing order and
+    /// not exceeding `max_tip_amount`.
+    pub suggested_tip_amounts: Option<Vec<i32>>,
+
+    /// Unique deep-linking parameter. If left empty, forwarded copies of the
+    /// sent message will have a Pay button, allowing multiple users to pay
+    /// directly from the forwarded message. If non-empty, forwarded copies
+    /// will have a URL button with a deep link to the bot instead.
+    pub start_parameter: Option<String>,
+
+    /// JSON-serialized data about the invoice, which will be shared with the
+    /// payment provider.
+    pub provider_data: Option<String>,
+
+    /// URL of the product photo for the invoice.
+    pub photo_url: Option<Url>,
+
+    pub photo_size: Option<u32>,
+
+    pub photo_width: Option<u32>,
+
+    pub photo_height: Option<u32>,
+
+    /// Pass `true` if you require the user's full name to complete the order.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub need_name: bool,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub need_phone_number: bool,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub need_email: bool,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub need_shipping_address: bool,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub send_phone_number_to_provider: bool,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub send_email_to_provider: bool,
+
+    /// Pass `true` if the final price depends on the shipping method, the bot
+    /// then receives a [`ShippingQuery`] to answer with
+    /// [`Telegram::answer_shipping_query`].
+    ///
+    /// [`ShippingQuery`]: crate::types::ShippingQuery
+    /// [`Telegram::answer_shipping_query`]: crate::Telegram::answer_shipping_query
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_flexible: bool,
+}
+
+impl NewInvoice {
+    pub fn new<S1, S2, S3, P>(
+        title: S1,
+        description: S2,
+        payload: S3,
+        currency: Currency,
+        prices: P,
+    ) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<String>,
+        P: IntoIterator<Item = LabeledPrice>,
+    {
+        Self {
+            title: title.into(),
+            description: description.into(),
+            payload: payload.into(),
+            provider_token: None,
+            currency,
+            prices: prices.into_iter().collect(),
+            max_tip_amount: None,
+            suggested_tip_amounts: None,
+            start_parameter: None,
+            provider_data: None,
+            photo_url: None,
+            photo_size: None,
+            photo_width: None,
+            photo_height: None,
+            need_name: false,
+            need_phone_number: false,
+            need_email: false,
+            need_shipping_address: false,
+            send_phone_number_to_provider: false,
+            send_email_to_provider: false,
+            is_flexible: false,
+        }
+    }
+
+    pub fn provider_token<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.provider_token = Some(val.into());
+        self
+    }
+
+    #[must_use]
+    pub fn max_tip_amount(mut self, val: i32) -> Self {
+        self.max_tip_amount = Some(val);
+        self
+    }
+
+    pub fn suggested_tip_amounts<A>(mut self, val: A) -> Self
+    where
+        A: IntoIterator<Item = i32>,
+    {
+        self.suggested_tip_amounts = Some(val.into_iter().collect());
+        self
+    }
+
+    pub fn start_parameter<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.start_parameter = Some(val.into());
+        self
+    }
+
+    pub fn provider_data<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.provider_data = Some(val.into());
+        self
+    }
+
+    #[must_use]
+    pub fn photo_url(mut self, val: Url) -> Self {
+        self.photo_url = Some(val);
+        self
+    }
+
+    #[must_use]
+    pub fn photo_size(mut self, val: u32) -> Self {
+        self.photo_size = Some(val);
+        self
+    }
+
+    #[must_use]
+    pub fn photo_width(mut self, val: u32) -> Self {
+        self.photo_width = Some(val);
+        self
+    }
+
+    #[must_use]
+    pub fn photo_height(mut self, val: u32) -> Self {
+        self.photo_height = Some(val);
+        self
+    }
+
+    #[must_use]
+    pub fn need_name(mut self, val: bool) -> Self {
+        self.need_name = val;
+        self
+    }
+
+    #[must_use]
+    pub fn need_phone_number(mut self, val: bool) -> Self {
+        self.need_phone_number = val;
+        self
+    }
+
+    #[must_use]
+    pub fn need_email(mut self, val: bool) -> Self {
+        self.need_email = val;
+        self
+    }
+
+    #[must_use]
+    pub fn need_shipping_address(mut self, val: bool) -> Self {
+        self.need_shipping_address = val;
+        self
+    }
+
+    #[must_use]
+    pub fn send_phone_number_to_provider(mut self, val: bool) -> Self {
+        self.send_phone_number_to_provider = val;
+        self
+    }
+
+    #[must_use]
+    pub fn send_email_to_provider(mut self, val: bool) -> Self {
+        self.send_email_to_provider = val;
+        self
+    }
+
+    #[must_use]
+    pub fn is_flexible(mut self, val: bool) -> Self {
+        self.is_flexible = val;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let invoice = NewInvoice::new(
+            "Tea",
+            "A pot of tea",
+            "order-1",
+            Currency::EUR,
+            [LabeledPrice::new("Tea", 450)],
+        )
+        .provider_token("TOKEN")
+        .need_shipping_address(true)
+        .is_flexible(true);
+
+        let expected = r#"{"title":"Tea","description":"A pot of tea","payload":"order-1","provider_token":"TOKEN","currency":"EUR","prices":[{"label":"Tea","amount":450}],"need_shipping_address":true,"is_flexible":true}"#;
+        assert_eq!(serde_json::to_string(&invoice).unwrap(), expected);
+    }
+}
@@ -348,6 +348,8 @@ pub enum Currency {
     XPT,
     /// SUCRE
     XSU,
+    /// Telegram Stars, for payments in digital goods and services
+    XTR,
     /// Code reserved for testing
     XTS,
     /// ADB Unit of Account
@@ -363,3 +365,39 @@ pub enum Currency {
     /// Zimbabwean dollar
     ZWL,
 }
+
+impl Currency {
+    /// Number of digits after the decimal point, i.e. the amount of the
+    /// smallest units in the main unit is `10.pow(exponent)`.
+    ///
+    /// Amounts in the Bot API, e.g. [`LabeledPrice::amount`], are in the
+    /// smallest units, see [`Money`] for converting them from and to decimals.
+    ///
+    /// [`LabeledPrice::amount`]: crate::types::LabeledPrice::amount
+    /// [`Money`]: crate::types::Money
+    #[must_use]
+    pub const fn exponent(self) -> u32 {
+        use Currency::*;
+
+        match self {
+            BIF | CLP | DJF | GNF | ISK | JPY | KMF | KRW | PYG | RWF | UGX | UYI | VND | VUV
+            | XAF | XOF | XPF | XTR => 0,
+            AED | AFN | ALL | AMD | ANG | AOA | ARS | AUD | AWG | AZN | BAM | BBD | BDT | BGN
+            | BMD | BND | BOB | BOV | BRL | BSD | BTN | BWP | BYN | BZD | CAD | CDF | CHE | CHF
+            | CHW | CNY | COP | COU | CRC | CUC | CUP | CVE | CZK | DKK | DOP | DZD | EGP | ERN
+            | ETB | EUR | FJD | FKP | GBP | GEL | GHS | GIP | GMD | GTQ | GYD | HKD | HNL | HRK
+            | HTG | HUF | IDR | ILS | INR | IRR | JMD | KES | KGS | KHR | KPW | KYD | KZT | LAK
+            | LBP | LKR | LRD | LSL | MAD | MDL | MGA | MKD | MMK | MNT | MOP | MRU | MUR | MVR
+            | MWK | MXN | MXV | MYR | MZN | NAD | NGN | NIO | NOK | NPR | NZD | PAB | PEN | PGK
+            | PHP | PKR | PLN | QAR | RON | RSD | RUB | SAR | SBD | SCR | SDG | SEK | SGD | SHP
+            | SLL | SOS | SRD | SSP | STN | SVC | SYP | SZL | THB | TJS | TMT | TOP | TRY | TTD
+            | TWD | TZS | UAH | USD | USN | UYU | UZS | VES | WST | XCD | YER | ZAR | ZMW | ZWL => {
+                2
+            }
+            BHD | IQD | JOD | KWD | LYD | OMR | TND => 3,
+            CLF | UYW => 4,
+            // no minor units, e.g. precious metals and testing codes
+            XAG | XAU | XBA | XBB | XBC | XBD | XDR | XPD | XPT | XSU | XTS | XUA | XXX => 0,
+        }
+    }
+}
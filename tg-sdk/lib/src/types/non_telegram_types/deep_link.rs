@@ -0,0 +1,146 @@
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use url::Url;
+
+/// The most characters Telegram accepts in the parameter of a deep link.
+pub const START_PARAMETER_LIMIT: usize = 64;
+
+/// Where a [deep link] opens the bot, with a payload for it.
+///
+/// Payloads are base64url-encoded in the link, so any bytes can go into
+/// them, but only 48 bytes fit.
+///
+/// ```
+/// use tg_flows::DeepLink;
+///
+/// let url = DeepLink::Start.url("ExampleBot", "ref-42").unwrap();
+/// assert_eq!(url.as_str(), "https://t.me/ExampleBot?start=cmVmLTQy");
+///
+/// let url = DeepLink::StartApp(Some("shop".to_owned())).url("ExampleBot", [1, 2]).unwrap();
+/// assert_eq!(url.as_str(), "https://t.me/ExampleBot/shop?startapp=AQI");
+/// ```
+///
+/// [deep link]: https://core.telegram.org/bots/features#deep-linking
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DeepLink {
+    /// A private chat with the bot. The bot gets `/start <payload>`, see
+    /// [`Message::start_payload`].
+    ///
+    /// [`Message::start_payload`]: crate::types::Message::start_payload
+    Start,
+
+    /// A group the user picks, to add the bot to. The bot gets
+    /// `/start <payload>` there.
+    StartGroup,
+
+    /// The attachment menu of a chat, if the bot has one.
+    StartAttach,
+
+    /// The Web App with the given short name, or the main Web App of the bot
+    /// if `None`. The payload is in its `initData` as `start_param`.
+    StartApp(Option<String>),
+}
+
+/// The payload of a [`DeepLink`] is more than [`START_PARAMETER_LIMIT`]
+/// characters once encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PayloadTooLongError {
+    /// Length of the encoded payload.
+    pub len: usize,
+}
+
+impl fmt::Display for PayloadTooLongError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "deep link payload is {} characters encoded, more than {START_PARAMETER_LIMIT}",
+            self.len
+        )
+    }
+}
+
+impl std::error::Error for PayloadTooLongError {}
+
+impl DeepLink {
+    /// Returns the link to the bot with `bot_username`, carrying `payload`.
+    ///
+    /// See also: [`Me::deep_link`].
+    ///
+    /// [`Me::deep_link`]: crate::types::Me::deep_link
+    pub fn url<P>(&self, bot_username: &str, payload: P) -> Result<Url, PayloadTooLongError>
+    where
+        P: AsRef<[u8]>,
+    {
+        let payload = encode_start_payload(payload)?;
+        let (app, key) = match self {
+            Self::Start => (None, "start"),
+            Self::StartGroup => (None, "startgroup"),
+            Self::StartAttach => (None, "startattach"),
+            Self::StartApp(app) => (app.as_deref(), "startapp"),
+        };
+
+        let url = match app {
+            Some(app) => format!("https://t.me/{bot_username}/{app}?{key}={payload}"),
+            None => format!("https://t.me/{bot_username}?{key}={payload}"),
+        };
+        Ok(url.parse().unwrap())
+    }
+}
+
+/// Encodes `payload` as a deep link parameter, which can only contain
+/// `A-Z`, `a-z`, `0-9`, `_` and `-`.
+pub fn encode_start_payload<P>(payload: P) -> Result<String, PayloadTooLongError>
+where
+    P: AsRef<[u8]>,
+{
+    let encoded = URL_SAFE_NO_PAD.encode(payload);
+    match encoded.len() {
+        len if len > START_PARAMETER_LIMIT => Err(PayloadTooLongError { len }),
+        _ => Ok(encoded),
+    }
+}
+
+/// Decodes a deep link parameter encoded by [`encode_start_payload`].
+///
+/// Returns `None` if it isn't base64url, e.g. for links made elsewhere.
+#[must_use]
+pub fn decode_start_payload(parameter: &str) -> Option<Vec<u8>> {
+    URL_SAFE_NO_PAD.decode(parameter).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{decode_start_payload, encode_start_payload, DeepLink};
+
+    #[test]
+    fn urls() {
+        let url = |link: DeepLink| link.url("ExampleBot", b"\xfb\xff").unwrap().to_string();
+        assert_eq!(url(DeepLink::Start), "https://t.me/ExampleBot?start=-_8");
+        assert_eq!(
+            url(DeepLink::StartGroup),
+            "https://t.me/ExampleBot?startgroup=-_8"
+        );
+        assert_eq!(
+            url(DeepLink::StartAttach),
+            "https://t.me/ExampleBot?startattach=-_8"
+        );
+        assert_eq!(
+            url(DeepLink::StartApp(None)),
+            "https://t.me/ExampleBot?startapp=-_8"
+        );
+    }
+
+    #[test]
+    fn payload_limit() {
+        let max = [7; 48];
+        let encoded = encode_start_payload(max).unwrap();
+        assert_eq!(encoded.len(), 64);
+        assert_eq!(decode_start_payload(&encoded).as_deref(), Some(&max[..]));
+
+        let err = DeepLink::Start.url("ExampleBot", [7; 49]).unwrap_err();
+        assert_eq!(err.len, 66);
+
+        assert_eq!(decode_start_payload("not base64!"), None);
+    }
+}
@@ -0,0 +1,266 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Currency, LabeledPrice};
+
+/// An amount of money in the smallest units of its currency, e.g. cents.
+///
+/// Decimals are parsed and formatted exactly according to
+/// [`Currency::exponent`], and arithmetic fails instead of mixing currencies.
+///
+/// ```
+/// use tg_flows::{Currency, Money};
+///
+/// let tea = Money::parse("4.50", Currency::EUR).unwrap();
+/// assert_eq!(tea.amount, 450);
+///
+/// let total = tea.checked_mul(2).and_then(|t| t.checked_add("0.99 EUR".parse()?));
+/// assert_eq!(total.unwrap().to_string(), "9.99 EUR");
+///
+/// assert!(tea.checked_add(Money::new(100, Currency::JPY)).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct Money {
+    /// Amount in the smallest units of `currency`.
+    pub amount: i64,
+
+    pub currency: Currency,
+}
+
+/// Why a [`Money`] operation failed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MoneyError {
+    /// The amounts are in different currencies.
+    CurrencyMismatch(Currency, Currency),
+
+    /// The result doesn't fit.
+    Overflow,
+
+    /// The decimal has more digits after the point than the currency allows.
+    TooPrecise,
+
+    /// The text isn't a decimal amount (with a currency code for
+    /// [`Money::from_str`]).
+    Invalid(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CurrencyMismatch(a, b) => write!(f, "can't mix {a:?} with {b:?}"),
+            Self::Overflow => f.write_str("amount overflow"),
+            Self::TooPrecise => f.write_str("amount is more precise than its currency"),
+            Self::Invalid(s) => write!(f, "invalid amount: {s:?}"),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+impl Money {
+    /// Creates money from an amount in the smallest units of `currency`.
+    #[must_use]
+    pub const fn new(amount: i64, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    /// Parses a decimal amount, e.g. `"10.99"` or `"-5"`, in `currency`.
+    ///
+    /// Digits after the point beyond [`Currency::exponent`] must be zeros.
+    pub fn parse(decimal: &str, currency: Currency) -> Result<Self, MoneyError> {
+        let invalid = || MoneyError::Invalid(decimal.to_owned());
+
+        let (negative, digits) = match decimal.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, decimal),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let is_number = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty()
+            || !is_number(whole)
+            || !is_number(fraction)
+            || (digits.contains('.') && fraction.is_empty())
+        {
+            return Err(invalid());
+        }
+
+        let exponent = currency.exponent() as usize;
+        let (fraction, rest) = fraction.split_at(fraction.len().min(exponent));
+        if rest.bytes().any(|b| b != b'0') {
+            return Err(MoneyError::TooPrecise);
+        }
+
+        // digits are valid at this point, so parsing only fails on overflow
+        let amount: i64 = format!("{whole}{fraction:0<exponent$}")
+            .parse()
+            .map_err(|_| MoneyError::Overflow)?;
+
+        Ok(Self::new(if negative { -amount } else { amount }, currency))
+    }
+
+    /// Returns the amount as a decimal, without the currency.
+    #[must_use]
+    pub fn to_decimal_string(&self) -> String {
+        let exponent = self.currency.exponent();
+        let scale = 10_u64.pow(exponent);
+        let sign = if self.amount < 0 { "-" } else { "" };
+        let (whole, fraction) = (
+            self.amount.unsigned_abs() / scale,
+            self.amount.unsigned_abs() % scale,
+        );
+
+        match exponent {
+            0 => format!("{sign}{whole}"),
+            e => format!("{sign}{whole}.{fraction:0width$}", width = e as usize),
+        }
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, MoneyError> {
+        let currency = self.same_currency(other)?;
+        let amount = self.amount.checked_add(other.amount);
+        Ok(Self::new(amount.ok_or(MoneyError::Overflow)?, currency))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, MoneyError> {
+        let currency = self.same_currency(other)?;
+        let amount = self.amount.checked_sub(other.amount);
+        Ok(Self::new(amount.ok_or(MoneyError::Overflow)?, currency))
+    }
+
+    /// Multiplies by a quantity.
+    pub fn checked_mul(self, quantity: i64) -> Result<Self, MoneyError> {
+        let amount = self.amount.checked_mul(quantity);
+        Ok(Self::new(
+            amount.ok_or(MoneyError::Overflow)?,
+            self.currency,
+        ))
+    }
+
+    /// Adds up `amounts`, all in `currency`.
+    pub fn sum<I>(currency: Currency, amounts: I) -> Result<Self, MoneyError>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        amounts
+            .into_iter()
+            .try_fold(Self::new(0, currency), Self::checked_add)
+    }
+
+    /// Converts to a [`LabeledPrice`], failing if the amount doesn't fit.
+    pub fn to_labeled_price<S>(self, label: S) -> Result<LabeledPrice, MoneyError>
+    where
+        S: Into<String>,
+    {
+        let amount = i32::try_from(self.amount).map_err(|_| MoneyError::Overflow)?;
+        Ok(LabeledPrice::new(label, amount))
+    }
+
+    fn same_currency(self, other: Self) -> Result<Currency, MoneyError> {
+        match self.currency == other.currency {
+            true => Ok(self.currency),
+            false => Err(MoneyError::CurrencyMismatch(self.currency, other.currency)),
+        }
+    }
+}
+
+/// Formats as `<decimal> <currency code>`, e.g. `10.99 USD`.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:?}", self.to_decimal_string(), self.currency)
+    }
+}
+
+/// Parses `<decimal> <currency code>`, e.g. `10.99 USD`.
+impl FromStr for Money {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MoneyError::Invalid(s.to_owned());
+
+        let (decimal, code) = s.trim().rsplit_once(' ').ok_or_else(invalid)?;
+        let currency = serde_json::from_value(serde_json::Value::String(code.to_owned()))
+            .map_err(|_| invalid())?;
+
+        Self::parse(decimal.trim(), currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Currency, LabeledPrice, Money, MoneyError};
+
+    #[test]
+    fn parse() {
+        let parse = |s| Money::parse(s, Currency::USD).map(|m| m.amount);
+
+        assert_eq!(parse("10.99"), Ok(1099));
+        assert_eq!(parse("10.9"), Ok(1090));
+        assert_eq!(parse("10"), Ok(1000));
+        assert_eq!(parse("-0.05"), Ok(-5));
+        assert_eq!(parse("1.2500"), Ok(125));
+        assert_eq!(parse("1.001"), Err(MoneyError::TooPrecise));
+        for invalid in ["", ".5", "1.", "1,5", "+1", "1e3", "--1", "1.-5"] {
+            assert_eq!(parse(invalid), Err(MoneyError::Invalid(invalid.to_owned())));
+        }
+        assert_eq!(parse("99999999999999999999"), Err(MoneyError::Overflow));
+
+        assert_eq!(Money::parse("500", Currency::JPY).unwrap().amount, 500);
+        assert_eq!(
+            Money::parse("0.5", Currency::JPY),
+            Err(MoneyError::TooPrecise)
+        );
+        assert_eq!(Money::parse("1.234", Currency::BHD).unwrap().amount, 1234);
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(Money::new(1099, Currency::USD).to_string(), "10.99 USD");
+        assert_eq!(Money::new(-5, Currency::EUR).to_string(), "-0.05 EUR");
+        assert_eq!(Money::new(500, Currency::JPY).to_string(), "500 JPY");
+        assert_eq!(Money::new(1, Currency::BHD).to_string(), "0.001 BHD");
+        assert_eq!(
+            Money::new(12345, Currency::CLF).to_decimal_string(),
+            "1.2345"
+        );
+    }
+
+    #[test]
+    fn from_str_round_trips() {
+        for s in ["10.99 USD", "-0.05 EUR", "500 JPY", "0.001 BHD"] {
+            assert_eq!(s.parse::<Money>().unwrap().to_string(), s);
+        }
+        assert!("10.99".parse::<Money>().is_err());
+        assert!("10.99 ABC".parse::<Money>().is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let usd = |amount| Money::new(amount, Currency::USD);
+
+        assert_eq!(usd(100).checked_add(usd(50)), Ok(usd(150)));
+        assert_eq!(usd(100).checked_sub(usd(150)), Ok(usd(-50)));
+        assert_eq!(usd(100).checked_mul(3), Ok(usd(300)));
+        assert_eq!(
+            usd(100).checked_add(Money::new(100, Currency::EUR)),
+            Err(MoneyError::CurrencyMismatch(Currency::USD, Currency::EUR))
+        );
+        assert_eq!(usd(i64::MAX).checked_add(usd(1)), Err(MoneyError::Overflow));
+
+        assert_eq!(Money::sum(Currency::USD, [usd(1), usd(2)]), Ok(usd(3)));
+        assert!(Money::sum(Currency::EUR, [usd(1)]).is_err());
+    }
+
+    #[test]
+    fn labeled_price() {
+        assert_eq!(
+            Money::new(450, Currency::EUR).to_labeled_price("Tea"),
+            Ok(LabeledPrice::new("Tea", 450))
+        );
+        assert_eq!(
+            Money::new(i64::from(i32::MAX) + 1, Currency::EUR).to_labeled_price("Tea"),
+            Err(MoneyError::Overflow)
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use chrono::{DateTime, Utc};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{de::Visitor, Deserialize, Serialize};
 
 use crate::types::serde_timestamp;
@@ -13,6 +15,23 @@ pub enum UntilDate {
     Forever,
 }
 
+impl UntilDate {
+    /// Returns the date `duration` from now, e.g. to mute a user for ten
+    /// minutes.
+    #[must_use]
+    pub fn after(duration: Duration) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let timestamp = i64::try_from(now.saturating_add(duration).as_secs());
+
+        match timestamp.map(|timestamp| Utc.timestamp_opt(timestamp, 0).single()) {
+            Ok(Some(date)) => Self::Date(date),
+            _ => Self::Forever,
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for UntilDate {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::types::EncryptedPassportElementKind;
+
 /// This object represents an error in the Telegram Passport element which was
 /// submitted that should be resolved by the user.
 ///
@@ -34,6 +36,21 @@ impl PassportElementError {
         self.kind = val;
         self
     }
+
+    /// An issue with an element as a whole, e.g. one that couldn't be
+    /// decrypted, for which `(&element.kind).into()` gives the type.
+    pub fn unspecified<S, H>(
+        message: S,
+        element_type: PassportElementErrorUnspecifiedType,
+        element_hash: H,
+    ) -> Self
+    where
+        S: Into<String>,
+        H: Into<String>,
+    {
+        let kind = PassportElementErrorUnspecified::new(element_type, element_hash);
+        Self::new(message, PassportElementErrorKind::Unspecified(kind))
+    }
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -42,28 +59,28 @@ pub enum PassportElementErrorKind {
     #[serde(rename = "data")]
     DataField(PassportElementErrorDataField),
 
-    #[serde(rename = "snake_case")]
+    #[serde(rename = "front_side")]
     FrontSide(PassportElementErrorFrontSide),
 
-    #[serde(rename = "snake_case")]
+    #[serde(rename = "reverse_side")]
     ReverseSide(PassportElementErrorReverseSide),
 
-    #[serde(rename = "snake_case")]
+    #[serde(rename = "selfie")]
     Selfie(PassportElementErrorSelfie),
 
-    #[serde(rename = "snake_case")]
+    #[serde(rename = "file")]
     File(PassportElementErrorFile),
 
-    #[serde(rename = "snake_case")]
+    #[serde(rename = "files")]
     Files(PassportElementErrorFiles),
 
-    #[serde(rename = "snake_case")]
+    #[serde(rename = "translation_file")]
     TranslationFile(PassportElementErrorTranslationFile),
 
-    #[serde(rename = "snake_case")]
+    #[serde(rename = "translation_files")]
     TranslationFiles(PassportElementErrorTranslationFiles),
 
-    #[serde(rename = "snake_case")]
+    #[serde(rename = "unspecified")]
     Unspecified(PassportElementErrorUnspecified),
 }
 
@@ -514,20 +531,47 @@ pub enum PassportElementErrorTranslationFilesType {
 #[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PassportElementErrorUnspecifiedType {
-    DataField,
-    FrontSide,
-    ReverseSide,
-    Selfie,
-    File,
-    Files,
-    TranslationFile,
-    TranslationFiles,
-    Unspecified,
+    PersonalDetails,
+    Passport,
+    DriverLicense,
+    IdentityCard,
+    InternalPassport,
+    Address,
+    UtilityBill,
+    BankStatement,
+    RentalAgreement,
+    PassportRegistration,
+    TemporaryRegistration,
+    PhoneNumber,
+    Email,
+}
+
+impl From<&EncryptedPassportElementKind> for PassportElementErrorUnspecifiedType {
+    fn from(kind: &EncryptedPassportElementKind) -> Self {
+        use EncryptedPassportElementKind as E;
+
+        match kind {
+            E::PersonalDetails(_) => Self::PersonalDetails,
+            E::Passport(_) => Self::Passport,
+            E::DriverLicense(_) => Self::DriverLicense,
+            E::IdentityCard(_) => Self::IdentityCard,
+            E::InternalPassport(_) => Self::InternalPassport,
+            E::Address(_) => Self::Address,
+            E::UtilityBill(_) => Self::UtilityBill,
+            E::BankStatement(_) => Self::BankStatement,
+            E::RentalAgreement(_) => Self::RentalAgreement,
+            E::PassportRegistration(_) => Self::PassportRegistration,
+            E::TemporaryRegistration(_) => Self::TemporaryRegistration,
+            E::PhoneNumber(_) => Self::PhoneNumber,
+            E::Email(_) => Self::Email,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::EncryptedPassportElement;
 
     #[test]
     fn serialize_data_field() {
@@ -545,4 +589,38 @@ mod tests {
             r#"{"message":"This is an error message!","source":"data","type":"internal_passport","field_name":"The field name","data_hash":"This is a data hash"}"#
         );
     }
+
+    #[test]
+    fn serialize_sources() {
+        let data = PassportElementError::new(
+            "blurry",
+            PassportElementErrorKind::TranslationFiles(PassportElementErrorTranslationFiles::new(
+                PassportElementErrorTranslationFilesType::UtilityBill,
+                ["a".to_owned(), "b".to_owned()],
+            )),
+        );
+
+        assert_eq!(
+            serde_json::to_string(&data).unwrap(),
+            r#"{"message":"blurry","source":"translation_files","type":"utility_bill","file_hashes":["a","b"]}"#
+        );
+    }
+
+    #[test]
+    fn unspecified() {
+        let element: EncryptedPassportElement = serde_json::from_str(
+            r#"{"type":"email","email":"ada@example.com","hash":"aGFzaA=="}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&PassportElementError::unspecified(
+                "bad",
+                (&element.kind).into(),
+                element.hash,
+            ))
+            .unwrap(),
+            r#"{"message":"bad","source":"unspecified","type":"email","element_hash":"aGFzaA=="}"#
+        );
+    }
 }
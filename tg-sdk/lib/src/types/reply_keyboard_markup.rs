@@ -71,6 +71,29 @@ impl KeyboardMarkup {
         }
     }
 
+    /// Lays `buttons` out in rows of `columns` buttons, the last row having
+    /// the rest.
+    ///
+    /// ```
+    /// use tg_flows::{KeyboardButton, KeyboardMarkup};
+    ///
+    /// let buttons = (1..=7).map(|n| KeyboardButton::new(n.to_string()));
+    /// let markup = KeyboardMarkup::grid(buttons, 3);
+    /// let rows: Vec<_> = markup.keyboard.iter().map(Vec::len).collect();
+    /// assert_eq!(rows, [3, 3, 1]);
+    /// assert_eq!(markup.keyboard[2][0].text, "7");
+    ///
+    /// let buttons = ["a", "b"].map(KeyboardButton::new);
+    /// assert_eq!(KeyboardMarkup::grid(buttons, 0).keyboard.len(), 2);
+    /// ```
+    pub fn grid<I>(buttons: I, columns: usize) -> Self
+    where
+        I: IntoIterator<Item = KeyboardButton>,
+    {
+        let buttons: Vec<_> = buttons.into_iter().collect();
+        Self::new(buttons.chunks(columns.max(1)).map(<[_]>::to_vec))
+    }
+
     pub fn append_row<R>(mut self, buttons: R) -> Self
     where
         R: IntoIterator<Item = KeyboardButton>,